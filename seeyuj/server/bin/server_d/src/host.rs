@@ -0,0 +1,395 @@
+//! # Hosting
+//!
+//! Several worlds in one `server_d` process (`serve`).
+//!
+//! ## Design
+//! - Each world runs `run_world` on its own thread, with its own
+//!   `Simulation` and ports (store, WAL, RNG, clock), tick rate and save
+//!   schedule: worlds share nothing but the process
+//! - A panic or an error (storage, failed tick...) stops its world only: it
+//!   is logged and the other worlds keep running. What the world had
+//!   persisted stays valid: the WAL is replayed on its next start (panics
+//!   must unwind: never build with `panic = "abort"`)
+//! - The hosted worlds are listed in a JSON file, read again every second:
+//!   worlds added are started, worlds removed are stopped (and saved),
+//!   worlds whose options changed are restarted with the new ones
+//! - A world that stopped on its own (error, panic, tick limit) is started
+//!   again only once its entry changes, or is removed and added back
+//!
+//! ## Hosted worlds file
+//! ```json
+//! [
+//!     { "world": "world_42", "options": { "tick_rate": 20, "save_interval": 200 } },
+//!     { "world": "world_7" }
+//! ]
+//! ```
+//! `options` takes the options of `run` (snake_case), with the same defaults.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use tracing::{error, info, info_span, warn};
+
+use crate::{run_world, RunOptions};
+
+/// How often the hosted worlds file is read again.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// One entry of the hosted worlds file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HostedWorld {
+    /// World ID to load
+    world: String,
+    #[serde(default)]
+    options: RunOptions,
+}
+
+/// A hosted world and its thread.
+struct Worker {
+    entry: HostedWorld,
+    running: Arc<AtomicBool>,
+    /// None once the world stopped and its outcome was reported
+    handle: Option<JoinHandle<Result<(), String>>>,
+}
+
+impl Worker {
+    fn start(data_dir: &Path, entry: HostedWorld) -> Result<Self, String> {
+        let running = Arc::new(AtomicBool::new(true));
+        let data_dir = data_dir.to_path_buf();
+        let (world_id, options, flag) =
+            (entry.world.clone(), entry.options.clone(), running.clone());
+
+        let handle = thread::Builder::new()
+            .name(format!("world-{}", entry.world))
+            .spawn(move || {
+                let _span = info_span!("world", id = %world_id).entered();
+                run_world(&data_dir, &world_id, &options, flag)
+            })
+            .map_err(|e| format!("Failed to start world '{}': {}", entry.world, e))?;
+
+        info!("Started world '{}'", entry.world);
+        Ok(Worker {
+            entry,
+            running,
+            handle: Some(handle),
+        })
+    }
+
+    /// Ask the world to stop after its current tick.
+    fn signal_stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Wait for the world to stop and report how it ended.
+    fn join(&mut self) {
+        let Some(handle) = self.handle.take() else {
+            return;
+        };
+        let world_id = &self.entry.world;
+        match handle.join() {
+            Ok(Ok(())) => info!("World '{}' stopped", world_id),
+            Ok(Err(e)) => error!("World '{}' stopped on error: {}", world_id, e),
+            Err(panic) => {
+                let message = panic
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                error!("World '{}' panicked: {}", world_id, message);
+            }
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_some_and(JoinHandle::is_finished)
+    }
+}
+
+/// Host the worlds listed in `list` until `running` is cleared.
+pub fn serve(data_dir: &Path, list: &Path, running: Arc<AtomicBool>) -> Result<(), String> {
+    // A missing or malformed file at start is a mistake, not an empty host
+    let mut hosted = read_list(list)?;
+    info!("Hosting worlds listed in {:?}", list);
+
+    let mut workers: BTreeMap<String, Worker> = BTreeMap::new();
+    let mut last_error: Option<String> = None;
+
+    while running.load(Ordering::SeqCst) {
+        reconcile(data_dir, &mut workers, hosted);
+
+        // Worlds that stopped on their own
+        for worker in workers.values_mut().filter(|w| w.is_finished()) {
+            worker.join();
+        }
+
+        let next = Instant::now() + RELOAD_INTERVAL;
+        while running.load(Ordering::SeqCst) && Instant::now() < next {
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        // On a bad edit, keep the current worlds until the file is fixed
+        hosted = match read_list(list) {
+            Ok(entries) => {
+                last_error = None;
+                entries
+            }
+            Err(e) => {
+                if last_error.as_ref() != Some(&e) {
+                    warn!("Keeping the current worlds: {}", e);
+                    last_error = Some(e);
+                }
+                workers.values().map(|w| w.entry.clone()).collect()
+            }
+        };
+    }
+
+    let live = workers.values().filter(|w| w.handle.is_some()).count();
+    info!("Stopping {} world(s)...", live);
+    for worker in workers.values() {
+        worker.signal_stop();
+    }
+    for worker in workers.values_mut() {
+        worker.join();
+    }
+
+    Ok(())
+}
+
+/// Start, stop and restart workers so that they match `hosted`.
+fn reconcile(data_dir: &Path, workers: &mut BTreeMap<String, Worker>, hosted: Vec<HostedWorld>) {
+    let wanted: BTreeMap<String, HostedWorld> = hosted
+        .into_iter()
+        .map(|entry| (entry.world.clone(), entry))
+        .collect();
+
+    // Removed or changed: stop them all first, then wait for each
+    let stale: Vec<String> = workers
+        .iter()
+        .filter(|(id, w)| wanted.get(*id) != Some(&w.entry))
+        .map(|(id, _)| id.clone())
+        .collect();
+    for id in &stale {
+        workers[id].signal_stop();
+    }
+    for id in &stale {
+        if let Some(mut worker) = workers.remove(id) {
+            worker.join();
+        }
+    }
+
+    // Added or changed
+    for (id, entry) in wanted {
+        if workers.contains_key(&id) {
+            continue;
+        }
+        match Worker::start(data_dir, entry) {
+            Ok(worker) => {
+                workers.insert(id, worker);
+            }
+            Err(e) => error!("{}", e),
+        }
+    }
+}
+
+/// Read the hosted worlds file.
+fn read_list(path: &Path) -> Result<Vec<HostedWorld>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read hosted worlds {:?}: {}", path, e))?;
+    let entries: Vec<HostedWorld> = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid hosted worlds {:?}: {}", path, e))?;
+
+    // Two simulations on one WAL would corrupt it
+    let mut seen = BTreeSet::new();
+    for entry in &entries {
+        if !seen.insert(entry.world.as_str()) {
+            return Err(format!("World '{}' is listed twice", entry.world));
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+    use std::path::PathBuf;
+
+    use sy_api::commands::{Command, LoadWorldCmd};
+    use sy_types::Tick;
+
+    use crate::{cmd_create, create_simulation};
+
+    /// A data directory of its own, holding worlds created with `seeds`
+    /// (IDs `world_<seed>`).
+    fn data_dir_with_worlds(name: &str, seeds: &[u64]) -> PathBuf {
+        let data_dir = temp_dir().join(format!("server_d_host_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&data_dir);
+        for &seed in seeds {
+            cmd_create(&data_dir, "Hosted", seed, 2, 2, None).unwrap();
+        }
+        data_dir
+    }
+
+    /// Runs until stopped, without spinning.
+    fn endless(world: &str) -> HostedWorld {
+        HostedWorld {
+            world: world.to_string(),
+            options: RunOptions {
+                tick_rate: 100,
+                ..RunOptions::default()
+            },
+        }
+    }
+
+    fn limited(world: &str, ticks: u64) -> HostedWorld {
+        HostedWorld {
+            world: world.to_string(),
+            options: RunOptions {
+                ticks,
+                ..RunOptions::default()
+            },
+        }
+    }
+
+    /// Tick of the world as saved on disk.
+    fn saved_tick(data_dir: &PathBuf, world_id: &str) -> Tick {
+        let mut sim = create_simulation(data_dir, world_id).unwrap();
+        sim.process_command(Command::LoadWorld(LoadWorldCmd {
+            world_id: world_id.to_string(),
+        }))
+        .unwrap();
+        sim.current_tick()
+    }
+
+    fn wait_until_finished(worker: &Worker) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !worker.is_finished() {
+            assert!(Instant::now() < deadline, "world did not stop");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn stop_all(workers: &mut BTreeMap<String, Worker>) {
+        for worker in workers.values() {
+            worker.signal_stop();
+        }
+        for worker in workers.values_mut() {
+            worker.join();
+        }
+    }
+
+    fn write_list(name: &str, json: &str) -> PathBuf {
+        let path = temp_dir().join(format!(
+            "server_d_list_{}_{}.json",
+            name,
+            std::process::id()
+        ));
+        fs::write(&path, json).unwrap();
+        path
+    }
+
+    #[test]
+    fn list_rejects_duplicates_and_unknown_options() {
+        let ok = write_list(
+            "ok",
+            r#"[{ "world": "world_1", "options": { "ticks": 5 } }, { "world": "world_2" }]"#,
+        );
+        let entries = read_list(&ok).unwrap();
+        assert_eq!(entries, vec![limited("world_1", 5), limited("world_2", 0)]);
+
+        let duplicate = write_list(
+            "duplicate",
+            r#"[{ "world": "world_1" }, { "world": "world_1" }]"#,
+        );
+        assert!(read_list(&duplicate).unwrap_err().contains("listed twice"));
+
+        let unknown = write_list(
+            "unknown",
+            r#"[{ "world": "world_1", "options": { "tick_speed": 5 } }]"#,
+        );
+        assert!(read_list(&unknown).is_err());
+    }
+
+    #[test]
+    fn reconcile_starts_added_and_stops_removed_worlds() {
+        let data_dir = data_dir_with_worlds("add_remove", &[1, 2]);
+        let mut workers = BTreeMap::new();
+
+        reconcile(&data_dir, &mut workers, vec![limited("world_1", 3)]);
+        assert_eq!(workers.keys().collect::<Vec<_>>(), vec!["world_1"]);
+
+        // Added without touching the first
+        reconcile(
+            &data_dir,
+            &mut workers,
+            vec![limited("world_1", 3), endless("world_2")],
+        );
+        assert_eq!(workers.len(), 2);
+        wait_until_finished(&workers["world_1"]);
+        workers.get_mut("world_1").unwrap().join();
+        assert_eq!(saved_tick(&data_dir, "world_1"), Tick(3));
+        assert!(!workers["world_2"].is_finished());
+
+        // Removed: stopped and saved, while world_1 stays as it is
+        reconcile(&data_dir, &mut workers, vec![limited("world_1", 3)]);
+        assert_eq!(workers.keys().collect::<Vec<_>>(), vec!["world_1"]);
+        assert!(workers["world_1"].handle.is_none());
+        let stopped_at = saved_tick(&data_dir, "world_2");
+
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(saved_tick(&data_dir, "world_2"), stopped_at);
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn reconcile_restarts_changed_worlds() {
+        let data_dir = data_dir_with_worlds("restart", &[1]);
+        let mut workers = BTreeMap::new();
+
+        reconcile(&data_dir, &mut workers, vec![endless("world_1")]);
+        assert!(!workers["world_1"].is_finished());
+
+        // Stopped, then started again with the new options
+        reconcile(&data_dir, &mut workers, vec![limited("world_1", 4)]);
+        assert_eq!(workers["world_1"].entry, limited("world_1", 4));
+        wait_until_finished(&workers["world_1"]);
+        workers.get_mut("world_1").unwrap().join();
+        let restarted_at = saved_tick(&data_dir, "world_1");
+        assert!(restarted_at >= Tick(4));
+
+        // Unchanged: a world that stopped on its own is not started again
+        reconcile(&data_dir, &mut workers, vec![limited("world_1", 4)]);
+        assert!(workers["world_1"].handle.is_none());
+        assert_eq!(saved_tick(&data_dir, "world_1"), restarted_at);
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn a_failing_world_leaves_the_others_running() {
+        let data_dir = data_dir_with_worlds("isolation", &[1]);
+        let mut workers = BTreeMap::new();
+
+        reconcile(
+            &data_dir,
+            &mut workers,
+            vec![endless("world_1"), endless("world_404")],
+        );
+        wait_until_finished(&workers["world_404"]);
+        workers.get_mut("world_404").unwrap().join();
+
+        // world_1 keeps ticking
+        thread::sleep(Duration::from_millis(100));
+        assert!(!workers["world_1"].is_finished());
+        stop_all(&mut workers);
+        assert!(saved_tick(&data_dir, "world_1") > Tick::ZERO);
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+}
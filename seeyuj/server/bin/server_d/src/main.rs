@@ -8,20 +8,57 @@
 //! - Auto-save at configurable intervals
 //! - Graceful shutdown with save
 //! - Recovery from crash (WAL replay)
-
-use std::path::PathBuf;
+//! - External inputs read from the world inbox and applied at their tick
+//! - Fork a world at an event or a tick into a new, independent world
+//! - Host several worlds in one process (`serve`, see `host`)
+//!
+//! ## Tick rate and offline catch-up
+//! `--tick-rate` paces the loop with a `FixedStepClock`. With `--catch-up`,
+//! the ticks missed while the server was down (computed from the wall-clock
+//! anchor in `worlds/{id}/anchor.json`) are first run as fast as possible,
+//! up to `--catch-up-max`; the decision is recorded as a `CatchUpDecided`
+//! event. The anchor never enters the world state, so replay is unaffected.
+//!
+//! ## Inputs
+//! While running, every `*.json` file dropped in `worlds/{id}/inbox/` is
+//! read as a `QueueInputCmd`: a command tagged with a source ID, a
+//! per-source sequence number and a target tick. Pending inputs are queued
+//! in (source, seq) order, whatever their file names. Accepted inputs are
+//! recorded in the WAL before their file is deleted; inputs already
+//! received (seq not above the source's last one, e.g. left over by a
+//! crash) are deleted too; rejected ones (late, malformed) are renamed to
+//! `*.rejected`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use serde::Deserialize;
 use tracing::{error, info, warn};
 use tracing_subscriber::EnvFilter;
 
-use sy_api::commands::{Command, CreateWorldCmd, EntityProperties, SpawnEntityCmd};
-use sy_core::ports::IWorldStore;
-use sy_core::Simulation;
-use sy_infra::{FileEventLog, FilesystemStore, Pcg32Rng, UnlimitedClock};
-use sy_types::{EntityKind, Position, RngSeed, WorldPos, ZoneId};
+use sy_api::archetypes::Archetype;
+use sy_api::commands::{
+    CatchUpCmd, Command, CreateWorldCmd, EntityProperties, QueueInputCmd, SpawnFromArchetypeCmd,
+};
+use sy_core::ports::{ISimClock, IWorldStore};
+use sy_core::{ForkAt, Simulation};
+use sy_infra::clock::unix_now_ms;
+use sy_infra::{
+    CatchUpPolicy, FileEventLog, FilesystemStore, FixedStepClock, Pcg32Rng, UnlimitedClock,
+    WallClockAnchor,
+};
+use sy_types::{EventId, Position, RngSeed, Tick, WorldPos, ZoneId};
+
+mod host;
+
+/// The simulation as wired by the server: real RNG, storage and WAL.
+type ServerSimulation = Simulation<Pcg32Rng, UnlimitedClock, FileEventLog, FilesystemStore>;
+
+/// Archetypes registered in new worlds unless `--archetypes` is given.
+const DEFAULT_ARCHETYPES: &str = include_str!("../../../content/archetypes.json");
 
 /// See-Yuj headless simulation server
 #[derive(Parser)]
@@ -59,6 +96,10 @@ enum Commands {
         /// Number of initial creatures to spawn
         #[arg(long, default_value = "5")]
         creatures: u32,
+
+        /// Archetype data file (JSON list; built-in content/archetypes.json if omitted)
+        #[arg(long)]
+        archetypes: Option<PathBuf>,
     },
 
     /// Run simulation on an existing world
@@ -67,19 +108,97 @@ enum Commands {
         #[arg(short, long)]
         world: String,
 
-        /// Number of ticks to run (0 = infinite)
-        #[arg(short, long, default_value = "0")]
-        ticks: u64,
+        #[command(flatten)]
+        options: RunOptions,
+    },
+
+    /// Fork a world into a new, independent world at an event or a tick
+    Fork {
+        /// World ID to fork (only read)
+        #[arg(short, long)]
+        world: String,
+
+        /// ID of the new world
+        #[arg(long)]
+        into: String,
+
+        /// Fork right after this event ID
+        #[arg(long, conflicts_with = "tick", required_unless_present = "tick")]
+        event: Option<u64>,
+
+        /// Fork at the end of this tick
+        #[arg(long)]
+        tick: Option<u64>,
+
+        /// Name of the new world (default: the parent's)
+        #[arg(short, long)]
+        name: Option<String>,
+    },
 
-        /// Auto-save interval in ticks (0 = no auto-save)
-        #[arg(long, default_value = "100")]
-        save_interval: u64,
+    /// Host several worlds, listed in a JSON file read again every second
+    Serve {
+        /// Hosted worlds (default: {data_dir}/hosted.json)
+        #[arg(long)]
+        worlds: Option<PathBuf>,
     },
 
     /// List available worlds
     List,
 }
 
+/// Options of the `run` subcommand, also the per-world options of `serve`
+/// (same names, snake_case, same defaults).
+#[derive(Args, Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RunOptions {
+    /// Number of ticks to run (0 = infinite)
+    #[arg(short, long, default_value = "0")]
+    ticks: u64,
+
+    /// Auto-save interval in ticks (0 = no auto-save)
+    #[arg(long, default_value = "100")]
+    save_interval: u64,
+
+    /// Threads used to tick zones (results do not depend on it)
+    #[arg(long, default_value = "1")]
+    tick_threads: usize,
+
+    /// Check world invariants after every tick and after recovery
+    /// (slow: walks and copies the whole world on every tick)
+    #[arg(long)]
+    strict: bool,
+
+    /// Advance unloaded zones with the coarse model every N ticks
+    /// (0 = unloaded zones are frozen)
+    #[arg(long, default_value = "0")]
+    coarse_interval: u64,
+
+    /// Ticks per second (0 = as fast as possible)
+    #[arg(long, default_value = "0")]
+    tick_rate: u32,
+
+    /// On start, run the ticks missed while the server was down
+    /// (requires --tick-rate)
+    #[arg(long)]
+    catch_up: bool,
+
+    /// Maximum number of ticks run to catch up (the rest is skipped)
+    #[arg(long, default_value = "72000")]
+    catch_up_max: u64,
+}
+
+impl Default for RunOptions {
+    /// The command-line defaults.
+    fn default() -> Self {
+        #[derive(Parser)]
+        struct Defaults {
+            #[command(flatten)]
+            options: RunOptions,
+        }
+        Defaults::parse_from(["server_d"]).options
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
 
@@ -111,12 +230,33 @@ fn main() {
             seed,
             resources,
             creatures,
-        } => cmd_create(&cli.data_dir, &name, seed, resources, creatures),
-        Commands::Run {
+            archetypes,
+        } => cmd_create(
+            &cli.data_dir,
+            &name,
+            seed,
+            resources,
+            creatures,
+            archetypes.as_deref(),
+        ),
+        Commands::Run { world, options } => run_world(&cli.data_dir, &world, &options, running),
+        Commands::Fork {
             world,
-            ticks,
-            save_interval,
-        } => cmd_run(&cli.data_dir, &world, ticks, save_interval, running),
+            into,
+            event,
+            tick,
+            name,
+        } => {
+            let at = match (event, tick) {
+                (Some(event), _) => ForkAt::Event(EventId::new(event)),
+                (None, tick) => ForkAt::Tick(Tick(tick.unwrap_or_default())),
+            };
+            cmd_fork(&cli.data_dir, &world, &into, at, name)
+        }
+        Commands::Serve { worlds } => {
+            let worlds = worlds.unwrap_or_else(|| cli.data_dir.join("hosted.json"));
+            host::serve(&cli.data_dir, &worlds, running)
+        }
         Commands::List => cmd_list(&cli.data_dir),
     };
 
@@ -147,9 +287,18 @@ fn cmd_create(
     seed: u64,
     resources: u32,
     creatures: u32,
+    archetypes: Option<&Path>,
 ) -> Result<(), String> {
     info!("Creating world '{}' with seed {}", name, seed);
 
+    let archetypes = match archetypes {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read archetypes {:?}: {}", path, e))?,
+        None => DEFAULT_ARCHETYPES.to_string(),
+    };
+    let archetypes =
+        Archetype::parse_list(&archetypes).map_err(|e| format!("Invalid archetype data: {}", e))?;
+
     let mut sim = create_simulation(data_dir, &format!("world_{}", seed))?;
 
     // Create the world
@@ -162,37 +311,27 @@ fn cmd_create(
     let world_id = sim.world().unwrap().id().to_string();
     info!("World created with ID: {}", world_id);
 
+    // Register archetypes: the world keeps its own copy
+    for archetype in archetypes {
+        sim.process_command(Command::DefineArchetype(archetype))
+            .map_err(|e| format!("Failed to define archetype: {}", e))?;
+    }
+
     // Spawn initial entities
     for i in 0..resources {
         let x = (i as i32 % 10) * 10;
         let y = (i as i32 / 10) * 10;
 
-        sim.process_command(Command::SpawnEntity(SpawnEntityCmd {
-            position: WorldPos::new(ZoneId::ORIGIN, Position::new(x, y, 0)),
-            kind: EntityKind::Resource,
-            properties: EntityProperties {
-                name: Some(format!("Resource_{}", i)),
-                amount: Some(100),
-                health: None,
-            },
-        }))
-        .map_err(|e| format!("Failed to spawn resource: {}", e))?;
+        sim.process_command(spawn_named("resource", format!("Resource_{}", i), x, y))
+            .map_err(|e| format!("Failed to spawn resource: {}", e))?;
     }
 
     for i in 0..creatures {
         let x = (i as i32 % 10) * 10 + 5;
         let y = (i as i32 / 10) * 10 + 5;
 
-        sim.process_command(Command::SpawnEntity(SpawnEntityCmd {
-            position: WorldPos::new(ZoneId::ORIGIN, Position::new(x, y, 0)),
-            kind: EntityKind::Creature,
-            properties: EntityProperties {
-                name: Some(format!("Creature_{}", i)),
-                amount: None,
-                health: Some(100),
-            },
-        }))
-        .map_err(|e| format!("Failed to spawn creature: {}", e))?;
+        sim.process_command(spawn_named("creature", format!("Creature_{}", i), x, y))
+            .map_err(|e| format!("Failed to spawn creature: {}", e))?;
     }
 
     // Final save
@@ -207,17 +346,39 @@ fn cmd_create(
     Ok(())
 }
 
-/// Run simulation
-fn cmd_run(
+/// Spawn from an archetype in the origin zone, with a custom name.
+fn spawn_named(archetype: &str, name: String, x: i32, y: i32) -> Command {
+    Command::SpawnFromArchetype(SpawnFromArchetypeCmd {
+        archetype: archetype.to_string(),
+        position: WorldPos::new(ZoneId::ORIGIN, Position::new(x, y, 0)),
+        properties: EntityProperties {
+            name: Some(name),
+            ..Default::default()
+        },
+        expires_at: None,
+    })
+}
+
+/// Run a world until `running` is cleared (Ctrl+C, or `serve` stopping
+/// it) or its tick limit is reached, then save it.
+fn run_world(
     data_dir: &PathBuf,
     world_id: &str,
-    max_ticks: u64,
-    save_interval: u64,
+    options: &RunOptions,
     running: Arc<AtomicBool>,
 ) -> Result<(), String> {
+    if options.catch_up && options.tick_rate == 0 {
+        return Err("--catch-up requires a --tick-rate".to_string());
+    }
+    let max_ticks = options.ticks;
+    let save_interval = options.save_interval;
+
     info!("Loading world '{}'", world_id);
 
     let mut sim = create_simulation(data_dir, world_id)?;
+    sim.set_tick_threads(options.tick_threads);
+    sim.set_strict(options.strict);
+    sim.set_coarse_interval(options.coarse_interval);
 
     // Load the world
     sim.process_command(Command::LoadWorld(sy_api::commands::LoadWorldCmd {
@@ -228,6 +389,14 @@ fn cmd_run(
     let start_tick = sim.current_tick();
     info!("World loaded at tick {}", start_tick);
 
+    let inbox = sim.store().inbox_dir(world_id);
+    fs::create_dir_all(&inbox).map_err(|e| format!("Failed to create inbox: {}", e))?;
+    info!("Reading inputs from {:?}", inbox);
+
+    // Ticks missed while down run unpaced, then the tick rate applies
+    let catch_up_until = catch_up(&mut sim, world_id, options)?;
+    let mut pacer = (options.tick_rate > 0).then(|| FixedStepClock::new(options.tick_rate));
+
     let mut ticks_run = 0u64;
     let mut last_save_tick = start_tick.as_u64();
 
@@ -241,6 +410,19 @@ fn cmd_run(
             break;
         }
 
+        if sim.current_tick() >= catch_up_until {
+            if sim.current_tick() == catch_up_until && catch_up_until > start_tick {
+                info!("Caught up at tick {}", catch_up_until);
+            }
+            if let Some(pacer) = pacer.as_mut() {
+                pacer.wait_for_next_tick();
+                pacer.advance();
+            }
+        }
+
+        // Queue inputs received since the last tick
+        ingest_inputs(&mut sim, &inbox)?;
+
         // Run one tick
         let events = sim
             .process_command(Command::Tick)
@@ -265,6 +447,7 @@ fn cmd_run(
             info!("Auto-saving at tick {}...", current_tick);
             sim.process_command(Command::SaveWorld)
                 .map_err(|e| format!("Auto-save failed: {}", e))?;
+            save_anchor(&sim, world_id, catch_up_until)?;
             last_save_tick = current_tick.as_u64();
         }
     }
@@ -273,6 +456,7 @@ fn cmd_run(
     info!("Saving world before shutdown...");
     sim.process_command(Command::Shutdown)
         .map_err(|e| format!("Shutdown save failed: {}", e))?;
+    save_anchor(&sim, world_id, catch_up_until)?;
 
     let final_tick = sim.current_tick();
     info!(
@@ -283,6 +467,213 @@ fn cmd_run(
     Ok(())
 }
 
+/// Decide how to make up for downtime, record the decision in the WAL and
+/// move the wall-clock anchor. Returns the tick at which the world is on time.
+///
+/// Without `--catch-up`, downtime is ignored: the world is on time now.
+fn catch_up(
+    sim: &mut ServerSimulation,
+    world_id: &str,
+    options: &RunOptions,
+) -> Result<Tick, String> {
+    let current = sim.current_tick();
+    let store = sim.store();
+    let anchor = store
+        .load_anchor(world_id)
+        .map_err(|e| format!("Failed to load anchor: {}", e))?;
+
+    let plan = match anchor {
+        Some(anchor) if options.catch_up => CatchUpPolicy {
+            ticks_per_second: options.tick_rate,
+            max_ticks: options.catch_up_max,
+        }
+        .plan(anchor, current, unix_now_ms()),
+        _ => {
+            store
+                .save_anchor(world_id, &WallClockAnchor::now(current))
+                .map_err(|e| format!("Failed to save anchor: {}", e))?;
+            return Ok(current);
+        }
+    };
+
+    if plan.missed_ticks > 0 {
+        sim.process_command(Command::CatchUp(CatchUpCmd {
+            offline_ms: plan.offline_ms,
+            missed_ticks: plan.missed_ticks,
+            ticks: plan.ticks,
+        }))
+        .map_err(|e| format!("Failed to record catch-up: {}", e))?;
+        sim.sync_event_log()
+            .map_err(|e| format!("Failed to record catch-up: {}", e))?;
+
+        if plan.skipped_ticks() > 0 {
+            warn!(
+                "Down for {} ms: catching up {} ticks, skipping {} (cap reached)",
+                plan.offline_ms,
+                plan.ticks,
+                plan.skipped_ticks()
+            );
+        } else {
+            info!(
+                "Down for {} ms: catching up {} ticks",
+                plan.offline_ms, plan.ticks
+            );
+        }
+    }
+
+    // An interrupted catch-up resumes from this anchor on the next start
+    sim.store()
+        .save_anchor(world_id, &plan.anchor)
+        .map_err(|e| format!("Failed to save anchor: {}", e))?;
+    Ok(plan.anchor.tick)
+}
+
+/// Anchor the current tick at the current time, unless the world is still
+/// catching up (the planned anchor stays, so no missed tick is forgotten).
+fn save_anchor(sim: &ServerSimulation, world_id: &str, catch_up_until: Tick) -> Result<(), String> {
+    let current = sim.current_tick();
+    if current < catch_up_until {
+        return Ok(());
+    }
+    sim.store()
+        .save_anchor(world_id, &WallClockAnchor::now(current))
+        .map_err(|e| format!("Failed to save anchor: {}", e))
+}
+
+/// Queue the inputs waiting in `inbox`, in (source, seq) order.
+///
+/// Input files are deleted only once the WAL is synced: after a crash they
+/// are read again, found already received by the per-source sequence check
+/// and deleted, so every input is applied exactly once.
+fn ingest_inputs(sim: &mut ServerSimulation, inbox: &Path) -> Result<(), String> {
+    let entries = fs::read_dir(inbox).map_err(|e| format!("Failed to read inbox: {}", e))?;
+    let files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    // Parse everything first: file names say nothing about the order
+    let mut pending = Vec::with_capacity(files.len());
+    for path in files {
+        let input = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| {
+                serde_json::from_str::<QueueInputCmd>(&json).map_err(|e| e.to_string())
+            });
+        match input {
+            Ok(cmd) => pending.push((cmd, path)),
+            Err(e) => reject_input(&path, &e)?,
+        }
+    }
+    pending.sort_by(|(a, a_path), (b, b_path)| {
+        (a.source, a.seq, a_path).cmp(&(b.source, b.seq, b_path))
+    });
+
+    let mut accepted = Vec::with_capacity(pending.len());
+    let mut received = Vec::new();
+    for (cmd, path) in pending {
+        let last_seq = sim
+            .world()
+            .and_then(|world| world.inputs.last_seq(cmd.source));
+        if last_seq.is_some_and(|last| cmd.seq <= last) {
+            received.push(path);
+            continue;
+        }
+
+        match sim.process_command(Command::QueueInput(cmd)) {
+            Ok(_) => accepted.push(path),
+            Err(e) => reject_input(&path, &e.to_string())?,
+        }
+    }
+
+    sim.sync_event_log()
+        .map_err(|e| format!("Failed to sync inputs: {}", e))?;
+    for path in accepted.iter().chain(&received) {
+        fs::remove_file(path).map_err(|e| format!("Failed to remove input: {}", e))?;
+    }
+    if !received.is_empty() {
+        info!("Dropped {} input(s) already received", received.len());
+    }
+    if !accepted.is_empty() {
+        info!(
+            "Queued {} input(s) at tick {}",
+            accepted.len(),
+            sim.current_tick()
+        );
+    }
+
+    Ok(())
+}
+
+/// Set aside an input that cannot be queued.
+fn reject_input(path: &Path, reason: &str) -> Result<(), String> {
+    warn!("Rejected input {:?}: {}", path, reason);
+    fs::rename(path, path.with_extension("rejected"))
+        .map_err(|e| format!("Failed to set aside input: {}", e))
+}
+
+/// Fork a world: copy its WAL, then cut the copy at the fork point.
+fn cmd_fork(
+    data_dir: &PathBuf,
+    world_id: &str,
+    fork_id: &str,
+    at: ForkAt,
+    name: Option<String>,
+) -> Result<(), String> {
+    if fork_id.is_empty()
+        || !fork_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!(
+            "Invalid world ID '{}' (letters, digits, '_' and '-' only)",
+            fork_id
+        ));
+    }
+
+    let mut store =
+        FilesystemStore::new(data_dir).map_err(|e| format!("Failed to open store: {}", e))?;
+    if !store.exists(world_id) {
+        return Err(format!("World not found: {}", world_id));
+    }
+    if store.exists(fork_id) {
+        return Err(format!("World already exists: {}", fork_id));
+    }
+
+    info!("Forking world '{}' into '{}'", world_id, fork_id);
+
+    let fork_events = store.events_dir(fork_id);
+    if let Some(dir) = fork_events.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create world dir: {}", e))?;
+    }
+    fs::copy(store.events_dir(world_id), &fork_events)
+        .map_err(|e| format!("Failed to copy event log: {}", e))?;
+
+    let result = FileEventLog::new(&fork_events).and_then(|mut fork_log| {
+        sy_tools::fork::fork_world(&mut store, &mut fork_log, world_id, fork_id, at, name)
+    });
+    let meta = match result {
+        Ok(meta) => meta,
+        Err(e) => {
+            // Nothing of the fork is kept (its meta was never written)
+            if let Err(cleanup) = store.delete_world(fork_id) {
+                warn!("Failed to remove partial fork '{}': {}", fork_id, cleanup);
+            }
+            return Err(format!("Failed to fork world: {}", e));
+        }
+    };
+
+    info!(
+        "World '{}' forked from '{}' at tick {} (last event {})",
+        fork_id, world_id, meta.current_tick, meta.last_event_id
+    );
+
+    Ok(())
+}
+
 /// List available worlds
 fn cmd_list(data_dir: &PathBuf) -> Result<(), String> {
     let store =
@@ -305,6 +696,12 @@ fn cmd_list(data_dir: &PathBuf) -> Result<(), String> {
                     meta.current_tick,
                     meta.seed.as_u64()
                 );
+                if let Some(fork) = &meta.forked_from {
+                    println!(
+                        "      forked from {} at tick {} (event {})",
+                        fork.parent_world_id, fork.tick, fork.event_id
+                    );
+                }
             } else {
                 println!("  {} (metadata unavailable)", world_id);
             }
@@ -315,10 +712,7 @@ fn cmd_list(data_dir: &PathBuf) -> Result<(), String> {
 }
 
 /// Create a simulation instance with real infrastructure
-fn create_simulation(
-    data_dir: &PathBuf,
-    world_id: &str,
-) -> Result<Simulation<Pcg32Rng, UnlimitedClock, FileEventLog, FilesystemStore>, String> {
+fn create_simulation(data_dir: &PathBuf, world_id: &str) -> Result<ServerSimulation, String> {
     let store =
         FilesystemStore::new(data_dir).map_err(|e| format!("Failed to create store: {}", e))?;
 
@@ -331,3 +725,76 @@ fn create_simulation(
 
     Ok(Simulation::new(rng, clock, event_log, store))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+    use sy_types::{EntityId, SourceId};
+
+    /// A fresh world and its inbox, in a directory of its own.
+    fn world_with_inbox(name: &str) -> (ServerSimulation, PathBuf) {
+        let data_dir = temp_dir().join(format!("server_d_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&data_dir);
+
+        let mut sim = create_simulation(&data_dir, "world_1").unwrap();
+        sim.process_command(Command::CreateWorld(CreateWorldCmd {
+            name: "Inbox".to_string(),
+            seed: RngSeed::new(1),
+        }))
+        .unwrap();
+
+        let inbox = sim.store().inbox_dir("world_1");
+        fs::create_dir_all(&inbox).unwrap();
+        (sim, inbox)
+    }
+
+    fn drop_input(inbox: &Path, file: &str, seq: u64) {
+        let cmd = QueueInputCmd {
+            source: SourceId::new(1),
+            seq,
+            at: Tick(5),
+            command: Box::new(Command::SleepEntity(EntityId::new(1))),
+        };
+        fs::write(inbox.join(file), serde_json::to_string(&cmd).unwrap()).unwrap();
+    }
+
+    fn inbox_files(inbox: &Path) -> Vec<String> {
+        let mut files: Vec<String> = fs::read_dir(inbox)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn inputs_are_queued_by_sequence_not_file_name() {
+        let (mut sim, inbox) = world_with_inbox("order");
+        // "input-10" sorts before "input-9"
+        drop_input(&inbox, "input-10.json", 10);
+        drop_input(&inbox, "input-9.json", 9);
+
+        ingest_inputs(&mut sim, &inbox).unwrap();
+
+        let world = sim.world().unwrap();
+        assert_eq!(world.inputs.len(), 2);
+        assert_eq!(world.inputs.last_seq(SourceId::new(1)), Some(10));
+        assert!(inbox_files(&inbox).is_empty());
+    }
+
+    #[test]
+    fn inputs_already_received_are_dropped_not_rejected() {
+        let (mut sim, inbox) = world_with_inbox("received");
+        drop_input(&inbox, "input-1.json", 1);
+        ingest_inputs(&mut sim, &inbox).unwrap();
+
+        // As left over by a crash between the WAL sync and the deletion
+        drop_input(&inbox, "input-1.json", 1);
+        fs::write(inbox.join("broken.json"), "{").unwrap();
+        ingest_inputs(&mut sim, &inbox).unwrap();
+
+        assert_eq!(sim.world().unwrap().inputs.len(), 1);
+        assert_eq!(inbox_files(&inbox), vec!["broken.rejected"]);
+    }
+}
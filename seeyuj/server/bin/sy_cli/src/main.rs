@@ -7,16 +7,18 @@
 //! - `dump`: Dump world state to JSON
 //! - `events`: List recent events
 //! - `entity`: Inspect a specific entity
+//! - `state-at`: Reconstruct the world at a past tick (read-only)
 
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
+use sy_api::queries::{EntityFilter, Page, Query, QueryResult};
 use sy_core::ports::IEventLog;
 use sy_core::ports::IWorldStore;
-use sy_core::World;
+use sy_core::{run_query, World};
 use sy_infra::{FileEventLog, FilesystemStore};
-use sy_types::EntityId;
+use sy_types::{EntityId, EntityKind, EntityState, Tick, ZoneId};
 
 /// See-Yuj CLI - World inspection and administration
 #[derive(Parser)]
@@ -84,6 +86,14 @@ enum Commands {
         /// Filter by kind (resource, creature, item, structure)
         #[arg(short, long)]
         kind: Option<String>,
+
+        /// Filter by zone ID
+        #[arg(short, long)]
+        zone: Option<u32>,
+
+        /// Filter by state (active, dormant, dead)
+        #[arg(short, long)]
+        state: Option<String>,
     },
 
     /// List zones
@@ -91,6 +101,41 @@ enum Commands {
         /// World ID
         world: String,
     },
+
+    /// Reconstruct the world as it was at a past tick (nothing is written)
+    StateAt {
+        /// World ID
+        world: String,
+
+        /// Tick to reconstruct (state after everything recorded at it)
+        #[arg(short, long)]
+        tick: u64,
+
+        /// What to show
+        #[arg(long, value_enum, default_value_t = StateView::Status)]
+        view: StateView,
+
+        /// Output file for the dump view (stdout if not specified)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Pretty print JSON (dump view)
+        #[arg(short, long)]
+        pretty: bool,
+    },
+}
+
+/// Views of a reconstructed world.
+#[derive(Clone, Copy, ValueEnum)]
+enum StateView {
+    /// Tick and statistics
+    Status,
+    /// All entities
+    Entities,
+    /// All zones
+    Zones,
+    /// Full world state as JSON
+    Dump,
 }
 
 fn main() {
@@ -109,8 +154,20 @@ fn main() {
             from_tick,
         } => cmd_events(&cli.data_dir, &world, count, from_tick),
         Commands::Entity { world, entity_id } => cmd_entity(&cli.data_dir, &world, entity_id),
-        Commands::Entities { world, kind } => cmd_entities(&cli.data_dir, &world, kind),
+        Commands::Entities {
+            world,
+            kind,
+            zone,
+            state,
+        } => cmd_entities(&cli.data_dir, &world, kind, zone, state),
         Commands::Zones { world } => cmd_zones(&cli.data_dir, &world),
+        Commands::StateAt {
+            world,
+            tick,
+            view,
+            output,
+            pretty,
+        } => cmd_state_at(&cli.data_dir, &world, Tick(tick), view, output, pretty),
     };
 
     if let Err(e) = result {
@@ -169,6 +226,13 @@ fn cmd_status(data_dir: &PathBuf, world_id: &str) -> Result<(), String> {
     println!("Last Event ID:   {}", meta.last_event_id);
     println!("WAL Events:      {}", wal_event_count);
     println!();
+    print_statistics(&world);
+
+    Ok(())
+}
+
+/// Entity and zone counts of a world.
+fn print_statistics(world: &World) {
     println!("=== Statistics ===");
     println!("Total Entities:  {}", world.entity_count());
     println!("Active Entities: {}", world.active_entity_count());
@@ -196,8 +260,6 @@ fn cmd_status(data_dir: &PathBuf, world_id: &str) -> Result<(), String> {
     println!("Creatures:  {}", creatures);
     println!("Items:      {}", items);
     println!("Structures: {}", structures);
-
-    Ok(())
 }
 
 /// Dump world state to JSON
@@ -208,11 +270,15 @@ fn cmd_dump(
     pretty: bool,
 ) -> Result<(), String> {
     let world = load_world(data_dir, world_id)?;
+    write_json(&world, output, pretty)
+}
 
+/// Write a world as JSON to a file or stdout.
+fn write_json(world: &World, output: Option<PathBuf>, pretty: bool) -> Result<(), String> {
     let json = if pretty {
-        serde_json::to_string_pretty(&world)
+        serde_json::to_string_pretty(world)
     } else {
-        serde_json::to_string(&world)
+        serde_json::to_string(world)
     }
     .map_err(|e| format!("Failed to serialize: {}", e))?;
 
@@ -284,6 +350,20 @@ fn cmd_entity(data_dir: &PathBuf, world_id: &str, entity_id: u64) -> Result<(),
     println!("State:      {:?}", entity.state);
     println!("Position:   {}", entity.position);
     println!("Created At: {}", entity.created_at);
+    if let Some(expires_at) = entity.expires_at {
+        println!("Expires At: {}", expires_at);
+    }
+    if let Some(attachment) = entity.attachment {
+        println!(
+            "Parent:     {} ({:?} on despawn)",
+            attachment.parent, attachment.on_parent_despawn
+        );
+    }
+    let children = world.children_of(entity.id);
+    if !children.is_empty() {
+        let ids: Vec<String> = children.iter().map(|id| id.to_string()).collect();
+        println!("Children:   {}", ids.join(", "));
+    }
     println!();
     println!("=== Properties ===");
     if let Some(name) = &entity.properties.name {
@@ -296,6 +376,14 @@ fn cmd_entity(data_dir: &PathBuf, world_id: &str, entity_id: u64) -> Result<(),
         println!("Health: {}", health);
     }
 
+    if !entity.components.is_empty() {
+        println!();
+        println!("=== Components ===");
+        for (name, value) in entity.components.iter() {
+            println!("{}: {}", name, value);
+        }
+    }
+
     Ok(())
 }
 
@@ -303,12 +391,23 @@ fn cmd_entity(data_dir: &PathBuf, world_id: &str, entity_id: u64) -> Result<(),
 fn cmd_entities(
     data_dir: &PathBuf,
     world_id: &str,
-    kind_filter: Option<String>,
+    kind: Option<String>,
+    zone: Option<u32>,
+    state: Option<String>,
 ) -> Result<(), String> {
-    let world = load_world(data_dir, world_id)?;
+    let filter = EntityFilter {
+        kind: kind.as_deref().map(parse_kind).transpose()?,
+        zone: zone.map(ZoneId::new),
+        state: state.as_deref().map(parse_state).transpose()?,
+        ..Default::default()
+    };
 
-    let kind_filter = kind_filter.map(|s| s.to_lowercase());
+    let world = load_world(data_dir, world_id)?;
+    print_entities(&world, &filter)
+}
 
+/// List the entities of a world matching `filter`.
+fn print_entities(world: &World, filter: &EntityFilter) -> Result<(), String> {
     println!("=== Entities ===");
     println!(
         "{:>8} | {:>10} | {:>8} | {:>20} | {:>10}",
@@ -316,49 +415,129 @@ fn cmd_entities(
     );
     println!("{}", "-".repeat(70));
 
-    for entity in world.entities.values() {
-        let kind_str = format!("{}", entity.kind).to_lowercase();
-
-        if let Some(ref filter) = kind_filter {
-            if !kind_str.contains(filter) {
-                continue;
-            }
+    let mut page = Page::default();
+    loop {
+        let query = Query::Entities {
+            filter: filter.clone(),
+            page,
+        };
+        let result = run_query(world, &query).map_err(|e| format!("Query failed: {}", e))?;
+        let QueryResult::Entities(entities) = result else {
+            return Err("Unexpected query result".to_string());
+        };
+
+        for entity in &entities.items {
+            let name = entity.properties.name.as_deref().unwrap_or("-");
+            println!(
+                "{:>8} | {:>10} | {:>8} | {:>20} | {:>10}",
+                entity.id.as_u64(),
+                entity.kind,
+                format!("{:?}", entity.state),
+                format!("{}", entity.position),
+                name
+            );
         }
 
-        let name = entity.properties.name.as_deref().unwrap_or("-");
-        println!(
-            "{:>8} | {:>10} | {:>8} | {:>20} | {:>10}",
-            entity.id.as_u64(),
-            entity.kind,
-            format!("{:?}", entity.state),
-            format!("{}", entity.position),
-            name
-        );
+        match entities.next {
+            Some(cursor) => page = Page::after(cursor, page.limit),
+            None => break,
+        }
     }
 
     Ok(())
 }
 
+/// Parse an entity kind name (case-insensitive).
+fn parse_kind(name: &str) -> Result<EntityKind, String> {
+    match name.to_lowercase().as_str() {
+        "resource" => Ok(EntityKind::Resource),
+        "creature" => Ok(EntityKind::Creature),
+        "item" => Ok(EntityKind::Item),
+        "structure" => Ok(EntityKind::Structure),
+        _ => Err(format!("Unknown entity kind: {}", name)),
+    }
+}
+
+/// Parse an entity state name (case-insensitive).
+fn parse_state(name: &str) -> Result<EntityState, String> {
+    match name.to_lowercase().as_str() {
+        "active" => Ok(EntityState::Active),
+        "dormant" => Ok(EntityState::Dormant),
+        "dead" => Ok(EntityState::Dead),
+        _ => Err(format!("Unknown entity state: {}", name)),
+    }
+}
+
 /// List zones
 fn cmd_zones(data_dir: &PathBuf, world_id: &str) -> Result<(), String> {
     let world = load_world(data_dir, world_id)?;
+    print_zones(&world);
+    Ok(())
+}
 
+/// List the zones of a world.
+fn print_zones(world: &World) {
     println!("=== Zones ===");
     println!(
-        "{:>8} | {:>20} | {:>8} | {:>10}",
-        "ID", "Name", "Loaded", "Entities"
+        "{:>8} | {:>20} | {:>8} | {:>10} | {:>12}",
+        "ID", "Name", "Loaded", "Entities", "Neighbours"
     );
-    println!("{}", "-".repeat(55));
+    println!("{}", "-".repeat(70));
 
     for zone in world.zones.values() {
         let name = zone.name.as_deref().unwrap_or("-");
         println!(
-            "{:>8} | {:>20} | {:>8} | {:>10}",
+            "{:>8} | {:>20} | {:>8} | {:>10} | {:>12}",
             zone.id.as_u32(),
             name,
             if zone.loaded { "Yes" } else { "No" },
-            zone.entities.len()
+            zone.entities.len(),
+            zone.neighbours
+                .iter()
+                .map(|z| z.as_u32().to_string())
+                .collect::<Vec<_>>()
+                .join(",")
         );
+        for portal in &zone.portals {
+            println!("{:>8}   portal {}", "", portal);
+        }
+    }
+}
+
+/// Reconstruct a world at a past tick and show it
+fn cmd_state_at(
+    data_dir: &PathBuf,
+    world_id: &str,
+    tick: Tick,
+    view: StateView,
+    output: Option<PathBuf>,
+    pretty: bool,
+) -> Result<(), String> {
+    let store =
+        FilesystemStore::new(data_dir).map_err(|e| format!("Failed to open store: {}", e))?;
+    if !store.exists(world_id) {
+        return Err(format!("World not found: {}", world_id));
+    }
+    let event_log = FileEventLog::new(store.events_dir(world_id))
+        .map_err(|e| format!("Failed to open event log: {}", e))?;
+
+    let world = sy_tools::replay::state_at(&store, &event_log, world_id, tick)
+        .map_err(|e| format!("Failed to reconstruct {}: {}", tick, e))?;
+
+    match view {
+        StateView::Status => {
+            println!("=== World at {} ===", tick);
+            println!("ID:              {}", world.id());
+            println!("Name:            {}", world.name());
+            println!("Tick:            {}", world.current_tick);
+            println!("Sim Time:        {}", world.sim_time);
+            println!("Last Event ID:   {}", world.meta.last_event_id);
+            println!();
+            print_statistics(&world);
+        }
+        StateView::Entities => print_entities(&world, &EntityFilter::default())?,
+        StateView::Zones => print_zones(&world),
+        StateView::Dump => write_json(&world, output, pretty)?,
     }
 
     Ok(())
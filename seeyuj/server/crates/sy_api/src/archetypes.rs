@@ -0,0 +1,131 @@
+//! # Archetypes
+//!
+//! Named entity templates: a kind, default properties and components, and
+//! behaviour tags.
+//!
+//! ## Design
+//! - Archetypes are plain data, defined in JSON files (a list of archetypes)
+//! - A world keeps its own registry (`DefineArchetype`), stored with it
+//! - `SpawnFromArchetype` resolves the template when the command runs and
+//!   records the fully resolved entity in `EntitySpawned`: editing a data
+//!   file never changes an existing world, nor its replay
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use sy_types::EntityKind;
+
+use crate::commands::EntityProperties;
+use crate::components::{Components, Tags};
+
+/// A named entity template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Archetype {
+    /// Unique name within a world
+    pub name: String,
+    /// Kind of the spawned entities
+    pub kind: EntityKind,
+    /// Default properties
+    #[serde(default)]
+    pub properties: EntityProperties,
+    /// Default components
+    #[serde(default)]
+    pub components: Components,
+    /// Behaviour tags (attached as the `Tags` component)
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+}
+
+impl Archetype {
+    /// Parse a data file: a JSON list of archetypes.
+    /// Names must be non-empty and unique, components float-free.
+    pub fn parse_list(json: &str) -> Result<Vec<Archetype>, String> {
+        let archetypes: Vec<Archetype> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+        let mut names = BTreeSet::new();
+        for archetype in &archetypes {
+            if archetype.name.is_empty() {
+                return Err("Archetype name cannot be empty".to_string());
+            }
+            if !names.insert(archetype.name.as_str()) {
+                return Err(format!("Duplicate archetype '{}'", archetype.name));
+            }
+            archetype
+                .components
+                .check()
+                .map_err(|e| format!("Archetype '{}': {}", archetype.name, e))?;
+        }
+
+        Ok(archetypes)
+    }
+
+    /// Properties of a spawned entity: the defaults, overridden by every
+    /// property set in `overrides`.
+    pub fn resolve_properties(&self, overrides: &EntityProperties) -> EntityProperties {
+        EntityProperties {
+            name: overrides
+                .name
+                .clone()
+                .or_else(|| self.properties.name.clone()),
+            amount: overrides.amount.or(self.properties.amount),
+            health: overrides.health.or(self.properties.health),
+        }
+    }
+
+    /// Components of a spawned entity: the defaults plus the tags.
+    pub fn resolve_components(&self) -> Result<Components, String> {
+        self.components.check()?;
+        let mut components = self.components.clone();
+        if !self.tags.is_empty() {
+            components.insert(&Tags(self.tags.clone()))?;
+        }
+        Ok(components)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &str = r#"[
+        {
+            "name": "wolf",
+            "kind": "Creature",
+            "properties": { "name": "Wolf", "health": 80 },
+            "components": { "velocity": { "dx": 1, "dy": 0, "dz": 0 } },
+            "tags": ["hostile", "pack"]
+        },
+        { "name": "rock", "kind": "Resource" }
+    ]"#;
+
+    #[test]
+    fn parse_and_resolve() {
+        let archetypes = Archetype::parse_list(DATA).unwrap();
+        assert_eq!(archetypes.len(), 2);
+
+        let wolf = &archetypes[0];
+        let properties = wolf.resolve_properties(&EntityProperties {
+            name: Some("Alpha".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(properties.name.as_deref(), Some("Alpha"));
+        assert_eq!(properties.health, Some(80));
+
+        let components = wolf.resolve_components().unwrap();
+        assert!(components.get::<Tags>().unwrap().contains("pack"));
+        assert_eq!(components.len(), 2);
+        assert!(archetypes[1].resolve_components().unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_rejects_float_components() {
+        let json = r#"[{ "name": "a", "kind": "Item", "components": { "speed": 1.5 } }]"#;
+        assert!(Archetype::parse_list(json).is_err());
+    }
+
+    #[test]
+    fn parse_rejects_duplicate_names() {
+        let json = r#"[{ "name": "a", "kind": "Item" }, { "name": "a", "kind": "Item" }]"#;
+        assert!(Archetype::parse_list(json).is_err());
+    }
+}
@@ -6,7 +6,13 @@
 //! Note: Phase 1 has NO player commands. Only internal/admin commands.
 
 use serde::{Deserialize, Serialize};
-use sy_types::{EntityId, RngSeed, WorldPos, ZoneId};
+use sy_types::{
+    EntityId, OnParentDespawn, Portal, Position, RngSeed, SourceId, Tick, TimerId, WorldPos, ZoneId,
+};
+
+use crate::archetypes::Archetype;
+use crate::components::Components;
+use crate::events::PropertyValue;
 
 /// Commands that can be issued to the simulation.
 /// These are internal commands, not player-facing.
@@ -22,12 +28,53 @@ pub enum Command {
     Tick,
     /// Advance simulation by N ticks
     TickN(u32),
+    /// Record the catch-up decision taken after downtime (the caller then
+    /// runs the ticks)
+    CatchUp(CatchUpCmd),
     /// Spawn an entity in the world
     SpawnEntity(SpawnEntityCmd),
+    /// Spawn an entity from an archetype of the world registry
+    SpawnFromArchetype(SpawnFromArchetypeCmd),
+    /// Register (or replace) an archetype in the world registry
+    DefineArchetype(Archetype),
     /// Remove an entity from the world
     DespawnEntity(EntityId),
+    /// Move an entity to a new position (possibly in another zone)
+    MoveEntity(MoveEntityCmd),
+    /// Put an active entity to sleep (Dormant: persisted but not processed)
+    SleepEntity(EntityId),
+    /// Wake a dormant entity (back to Active)
+    WakeEntity(EntityId),
+    /// Set a named entity property (admin)
+    SetEntityProperty(SetEntityPropertyCmd),
+    /// Set or clear the expiry tick of an entity
+    SetEntityExpiry(SetEntityExpiryCmd),
+    /// Attach an entity to a parent (moved from its previous parent, if any)
+    AttachEntity(AttachEntityCmd),
+    /// Detach an entity from its parent
+    DetachEntity(EntityId),
+    /// Schedule a command to run at a future tick (optionally repeating)
+    ScheduleCommand(ScheduleCmd),
+    /// Cancel a scheduled command
+    CancelTimer(TimerId),
+    /// Queue an external input for a future tick
+    QueueInput(QueueInputCmd),
     /// Create a new zone
     CreateZone(CreateZoneCmd),
+    /// Load a zone into active simulation
+    LoadZone(ZoneId),
+    /// Unload a zone from active simulation (its entities stop ticking)
+    UnloadZone(ZoneId),
+    /// Declare two zones as neighbours
+    LinkZones(LinkZonesCmd),
+    /// Remove a neighbour link (and the portals between both zones)
+    UnlinkZones(LinkZonesCmd),
+    /// Add (or replace) a portal in a zone; links the zones if needed
+    AddPortal(AddPortalCmd),
+    /// Remove the portal at a position of a zone
+    RemovePortal(RemovePortalCmd),
+    /// Run commands as one unit: all succeed, or nothing is applied
+    Batch(Vec<Command>),
     /// Shutdown the server gracefully
     Shutdown,
 }
@@ -41,6 +88,18 @@ pub struct CreateWorldCmd {
     pub seed: RngSeed,
 }
 
+/// Catch-up decision after the server was down.
+/// Wall-clock figures are computed outside the core and only recorded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatchUpCmd {
+    /// Wall-clock time the world was behind, in milliseconds
+    pub offline_ms: u64,
+    /// Ticks missed during that time
+    pub missed_ticks: u64,
+    /// Ticks that will be run to catch up (at most `missed_ticks`)
+    pub ticks: u64,
+}
+
 /// Command to load an existing world
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoadWorldCmd {
@@ -57,10 +116,128 @@ pub struct SpawnEntityCmd {
     pub kind: sy_types::EntityKind,
     /// Initial properties (key-value for flexibility)
     pub properties: EntityProperties,
+    /// Initial typed components
+    #[serde(default)]
+    pub components: Components,
+    /// Tick at which the entity expires (None = never, must be in the future)
+    #[serde(default)]
+    pub expires_at: Option<Tick>,
+}
+
+/// Command to spawn an entity from an archetype
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnFromArchetypeCmd {
+    /// Archetype name
+    pub archetype: String,
+    /// Where to spawn
+    pub position: WorldPos,
+    /// Properties overriding the archetype defaults (None = keep default)
+    #[serde(default)]
+    pub properties: EntityProperties,
+    /// Tick at which the entity expires (None = never, must be in the future)
+    #[serde(default)]
+    pub expires_at: Option<Tick>,
+}
+
+/// Command to move an entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveEntityCmd {
+    /// Entity to move
+    pub entity_id: EntityId,
+    /// Destination
+    pub to: WorldPos,
+}
+
+/// Command to set a named entity property
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetEntityPropertyCmd {
+    /// Target entity
+    pub entity_id: EntityId,
+    /// Property name (see `EntityProperties::NAMES`)
+    pub property: String,
+    /// New value (`PropertyValue::None` clears the property)
+    pub value: PropertyValue,
+}
+
+/// Command to set or clear the expiry tick of an entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetEntityExpiryCmd {
+    /// Target entity
+    pub entity_id: EntityId,
+    /// Expiry tick (None = never expire, otherwise must be in the future)
+    pub expires_at: Option<Tick>,
+}
+
+/// Command to attach an entity to a parent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachEntityCmd {
+    /// Entity to attach (with its own children)
+    pub entity_id: EntityId,
+    /// Entity holding it
+    pub parent_id: EntityId,
+    /// What happens to the entity when the parent is despawned
+    #[serde(default)]
+    pub on_parent_despawn: OnParentDespawn,
+}
+
+/// Command to schedule another command at a future tick
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleCmd {
+    /// Tick at which the command fires (must be in the future)
+    pub at: Tick,
+    /// Repeat period in ticks (None = fire once)
+    pub repeat_every: Option<u64>,
+    /// The command to run
+    pub command: Box<Command>,
+}
+
+/// Command to queue an external input for a future tick
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueInputCmd {
+    /// Who sent the input
+    pub source: SourceId,
+    /// Sequence number, strictly increasing per source
+    pub seq: u64,
+    /// Tick at which the input is applied (must be in the future)
+    pub at: Tick,
+    /// The command to run
+    pub command: Box<Command>,
+}
+
+impl Command {
+    /// Whether this command may be run from the timer queue.
+    /// Lifecycle and tick commands are driven from outside the world.
+    pub fn is_schedulable(&self) -> bool {
+        match self {
+            Command::CreateWorld(_)
+            | Command::LoadWorld(_)
+            | Command::SaveWorld
+            | Command::Tick
+            | Command::TickN(_)
+            | Command::CatchUp(_)
+            | Command::QueueInput(_)
+            | Command::Shutdown => false,
+            Command::Batch(commands) => commands.iter().all(Command::is_schedulable),
+            _ => true,
+        }
+    }
+
+    /// Whether this command may run inside a `Batch`.
+    /// Commands touching storage or replacing the world cannot be undone.
+    pub fn is_batchable(&self) -> bool {
+        match self {
+            Command::CreateWorld(_)
+            | Command::LoadWorld(_)
+            | Command::SaveWorld
+            | Command::Shutdown => false,
+            Command::Batch(commands) => commands.iter().all(Command::is_batchable),
+            _ => true,
+        }
+    }
 }
 
 /// Entity properties (simple key-value for Phase 1)
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct EntityProperties {
     /// Display name (optional)
     pub name: Option<String>,
@@ -70,6 +247,57 @@ pub struct EntityProperties {
     pub health: Option<u32>,
 }
 
+impl EntityProperties {
+    /// Names of the properties addressable by `SetEntityProperty`.
+    pub const NAMES: [&'static str; 3] = ["name", "amount", "health"];
+
+    /// Check that `value` has the right type for `property`.
+    pub fn check(property: &str, value: &PropertyValue) -> Result<(), String> {
+        match (property, value) {
+            (_, PropertyValue::None) if Self::NAMES.contains(&property) => Ok(()),
+            ("name", PropertyValue::String(_)) => Ok(()),
+            ("amount" | "health", PropertyValue::UInt(v)) => {
+                if *v <= u32::MAX as u64 {
+                    Ok(())
+                } else {
+                    Err(format!("Value {} out of range for '{}' (u32)", v, property))
+                }
+            }
+            ("name", _) => Err("Property 'name' expects a String".to_string()),
+            ("amount" | "health", _) => Err(format!("Property '{}' expects a UInt", property)),
+            _ => Err(format!("Unknown property '{}'", property)),
+        }
+    }
+
+    /// Read a property as a generic value (`None` if unset).
+    pub fn get(&self, property: &str) -> Option<PropertyValue> {
+        let value = match property {
+            "name" => self.name.clone().map(PropertyValue::String),
+            "amount" => self.amount.map(|v| PropertyValue::UInt(v as u64)),
+            "health" => self.health.map(|v| PropertyValue::UInt(v as u64)),
+            _ => return None,
+        };
+        Some(value.unwrap_or_default())
+    }
+
+    /// Write a property from a generic value, after type checking.
+    pub fn set(&mut self, property: &str, value: &PropertyValue) -> Result<(), String> {
+        Self::check(property, value)?;
+
+        match (property, value) {
+            ("name", PropertyValue::String(s)) => self.name = Some(s.clone()),
+            ("name", _) => self.name = None,
+            ("amount", PropertyValue::UInt(v)) => self.amount = Some(*v as u32),
+            ("amount", _) => self.amount = None,
+            ("health", PropertyValue::UInt(v)) => self.health = Some(*v as u32),
+            ("health", _) => self.health = None,
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
 /// Command to create a new zone
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateZoneCmd {
@@ -78,3 +306,28 @@ pub struct CreateZoneCmd {
     /// Optional name
     pub name: Option<String>,
 }
+
+/// Command to link (or unlink) two neighbouring zones
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkZonesCmd {
+    pub a: ZoneId,
+    pub b: ZoneId,
+}
+
+/// Command to add a portal to a zone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddPortalCmd {
+    /// Zone containing the portal entrance
+    pub zone_id: ZoneId,
+    /// Entrance position and destination
+    pub portal: Portal,
+}
+
+/// Command to remove a portal from a zone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemovePortalCmd {
+    /// Zone containing the portal entrance
+    pub zone_id: ZoneId,
+    /// Entrance position
+    pub from: Position,
+}
@@ -0,0 +1,246 @@
+//! # Components
+//!
+//! Typed, serializable per-entity data beyond `EntityProperties`.
+//!
+//! ## Design
+//! - A component is any serde type with a stable `NAME`
+//! - Components are stored by name in a BTreeMap (deterministic order)
+//! - Values are kept in serialized form so the set of component types
+//!   does not need to be known by the core, snapshots or the WAL
+//!
+//! ## Determinism
+//! `canonical_bytes` is stable for a given value: maps are ordered by key
+//! and the encoding never depends on insertion order. Values are float-free:
+//! non-integer numbers are rejected on insert and by command validation
+//! (see `Components::check`), so they never reach snapshots or the hash.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sy_types::Position;
+
+/// A typed component that can be attached to an entity.
+pub trait Component: Serialize + DeserializeOwned {
+    /// Stable name used as storage key (never change it once persisted).
+    const NAME: &'static str;
+}
+
+/// Serialized value of a single component.
+pub type ComponentValue = serde_json::Value;
+
+/// Components attached to an entity, keyed by component name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Components(BTreeMap<String, ComponentValue>);
+
+impl Components {
+    pub fn new() -> Self {
+        Components(BTreeMap::new())
+    }
+
+    /// Attach (or replace) a typed component.
+    /// Fails if it serializes to a non-integer number.
+    pub fn insert<T: Component>(&mut self, component: &T) -> Result<(), String> {
+        let value = serde_json::to_value(component).map_err(|e| e.to_string())?;
+        self.insert_raw(T::NAME, value)
+    }
+
+    /// Builder-style variant of `insert`.
+    pub fn with<T: Component>(mut self, component: &T) -> Result<Self, String> {
+        self.insert(component)?;
+        Ok(self)
+    }
+
+    /// Get a typed component. Returns None if absent or if the stored
+    /// value does not match the requested type.
+    pub fn get<T: Component>(&self) -> Option<T> {
+        self.0
+            .get(T::NAME)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+    }
+
+    /// Remove a typed component. Returns true if it was present.
+    pub fn remove<T: Component>(&mut self) -> bool {
+        self.0.remove(T::NAME).is_some()
+    }
+
+    /// Check if a typed component is attached.
+    pub fn contains<T: Component>(&self) -> bool {
+        self.0.contains_key(T::NAME)
+    }
+
+    /// Set a raw (untyped) component value by name.
+    /// Fails if the value holds a non-integer number.
+    pub fn insert_raw(
+        &mut self,
+        name: impl Into<String>,
+        value: ComponentValue,
+    ) -> Result<(), String> {
+        let name = name.into();
+        check_value(&name, &value)?;
+        self.0.insert(name, value);
+        Ok(())
+    }
+
+    /// Get a raw (untyped) component value by name.
+    pub fn get_raw(&self, name: &str) -> Option<&ComponentValue> {
+        self.0.get(name)
+    }
+
+    /// Remove a raw component by name.
+    pub fn remove_raw(&mut self, name: &str) -> Option<ComponentValue> {
+        self.0.remove(name)
+    }
+
+    /// Iterate components in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &ComponentValue)> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Check that no value holds a non-integer number (components built by
+    /// deserialization, e.g. from commands or data files, skip `insert`).
+    pub fn check(&self) -> Result<(), String> {
+        self.0
+            .iter()
+            .try_for_each(|(name, value)| check_value(name, value))
+    }
+
+    /// Stable byte encoding of a component value (for hashing).
+    pub fn canonical_bytes(value: &ComponentValue) -> Vec<u8> {
+        // serde_json::Value objects are BTreeMap-backed: keys are sorted
+        serde_json::to_vec(value).unwrap_or_default()
+    }
+}
+
+/// Reject floats anywhere in `value`: they would break the float-free
+/// guarantee of the simulation state.
+fn check_value(name: &str, value: &ComponentValue) -> Result<(), String> {
+    match value {
+        serde_json::Value::Number(n) if n.is_f64() => Err(format!(
+            "Component '{}' holds a non-integer number ({})",
+            name, n
+        )),
+        serde_json::Value::Array(items) => items.iter().try_for_each(|v| check_value(name, v)),
+        serde_json::Value::Object(fields) => fields.values().try_for_each(|v| check_value(name, v)),
+        _ => Ok(()),
+    }
+}
+
+// ============================================================================
+// Built-in components
+// ============================================================================
+
+/// Constant displacement applied every tick (local coordinates).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Velocity {
+    pub dx: i32,
+    pub dy: i32,
+    pub dz: i32,
+}
+
+impl Component for Velocity {
+    const NAME: &'static str = "velocity";
+}
+
+/// Local position to walk towards, at most `speed` units per axis per tick.
+/// The component stays attached once the target is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveTarget {
+    pub target: Position,
+    pub speed: u32,
+}
+
+impl Component for MoveTarget {
+    const NAME: &'static str = "move_target";
+}
+
+/// Behaviour tags, usually given by the entity's archetype.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Tags(pub BTreeSet<String>);
+
+impl Tags {
+    pub fn contains(&self, tag: &str) -> bool {
+        self.0.contains(tag)
+    }
+}
+
+impl Component for Tags {
+    const NAME: &'static str = "tags";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Hunger {
+        level: u32,
+    }
+
+    impl Component for Hunger {
+        const NAME: &'static str = "hunger";
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Tag(String);
+
+    impl Component for Tag {
+        const NAME: &'static str = "tag";
+    }
+
+    #[test]
+    fn insert_get_remove() {
+        let mut components = Components::new();
+        components.insert(&Hunger { level: 3 }).unwrap();
+
+        assert_eq!(components.get::<Hunger>(), Some(Hunger { level: 3 }));
+        assert!(components.get::<Tag>().is_none());
+
+        assert!(components.remove::<Hunger>());
+        assert!(components.is_empty());
+    }
+
+    #[test]
+    fn non_integer_numbers_are_rejected() {
+        #[derive(Serialize, Deserialize)]
+        struct Speed(f32);
+
+        impl Component for Speed {
+            const NAME: &'static str = "speed";
+        }
+
+        let mut components = Components::new();
+        assert!(components.insert(&Speed(1.5)).is_err());
+        assert!(components
+            .insert_raw("speed", serde_json::json!({ "v": [1, 2.5] }))
+            .is_err());
+        assert!(components
+            .insert_raw("speed", serde_json::json!({ "v": [1, -2] }))
+            .is_ok());
+
+        let parsed: Components = serde_json::from_str(r#"{ "speed": 1.0 }"#).unwrap();
+        assert!(parsed.check().is_err());
+        assert!(components.check().is_ok());
+    }
+
+    #[test]
+    fn iteration_is_ordered_by_name() {
+        let components = Components::new()
+            .with(&Tag("x".to_string()))
+            .unwrap()
+            .with(&Hunger { level: 1 })
+            .unwrap();
+
+        let names: Vec<&str> = components.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(names, vec!["hunger", "tag"]);
+    }
+}
@@ -3,7 +3,7 @@
 //! Typed errors for validation, refusal, and compatibility issues.
 
 use serde::{Deserialize, Serialize};
-use sy_types::{EntityId, ZoneId};
+use sy_types::{EntityId, Tick, ZoneId};
 
 /// API-level errors (command processing failures)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,10 +20,16 @@ pub enum ApiError {
     ZoneNotFound(ZoneId),
     /// Zone already exists
     ZoneAlreadyExists(ZoneId),
+    /// Archetype not registered in the world
+    ArchetypeNotFound(String),
     /// Invalid command
     InvalidCommand(String),
     /// Validation failed
     ValidationFailed(Vec<ValidationError>),
+    /// A step of a batch failed (the whole batch was rolled back)
+    BatchFailed { index: usize, error: Box<ApiError> },
+    /// The world broke an invariant (strict mode), one line per violation
+    InvariantViolated { tick: Tick, violations: Vec<String> },
     /// Storage error
     StorageError(String),
     /// Internal error
@@ -39,10 +45,20 @@ impl std::fmt::Display for ApiError {
             ApiError::EntityNotFound(id) => write!(f, "Entity not found: {}", id),
             ApiError::ZoneNotFound(id) => write!(f, "Zone not found: {}", id),
             ApiError::ZoneAlreadyExists(id) => write!(f, "Zone already exists: {}", id),
+            ApiError::ArchetypeNotFound(name) => write!(f, "Archetype not found: {}", name),
             ApiError::InvalidCommand(msg) => write!(f, "Invalid command: {}", msg),
             ApiError::ValidationFailed(errors) => {
                 write!(f, "Validation failed: {:?}", errors)
             }
+            ApiError::BatchFailed { index, error } => {
+                write!(f, "Batch rolled back, step {} failed: {}", index, error)
+            }
+            ApiError::InvariantViolated { tick, violations } => write!(
+                f,
+                "Invariant violated at tick {}: {}",
+                tick,
+                violations.join("; ")
+            ),
             ApiError::StorageError(msg) => write!(f, "Storage error: {}", msg),
             ApiError::InternalError(msg) => write!(f, "Internal error: {}", msg),
         }
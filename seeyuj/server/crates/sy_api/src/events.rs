@@ -11,10 +11,13 @@
 
 use serde::{Deserialize, Serialize};
 use sy_types::{
-    EntityId, EntityKind, EntityState, EventId, RngSeed, SimTime, Tick, WorldPos, ZoneId,
+    Attachment, EntityId, EntityKind, EntityState, EventId, Fixed, Portal, Position, RngSeed,
+    SimTime, SourceId, Tick, TimerId, WorldPos, ZoneId,
 };
 
-use crate::commands::EntityProperties;
+use crate::archetypes::Archetype;
+use crate::commands::{Command, EntityProperties};
+use crate::components::Components;
 
 /// An event that occurred in the simulation.
 /// Events are the source of truth for state changes.
@@ -78,6 +81,14 @@ pub enum EventData {
         sim_time: SimTime,
         entities_processed: u32,
     },
+    /// Catch-up decided after downtime: `ticks` missed ticks are run,
+    /// `skipped_ticks` are dropped (cap reached)
+    CatchUpDecided {
+        offline_ms: u64,
+        missed_ticks: u64,
+        ticks: u64,
+        skipped_ticks: u64,
+    },
 
     // ========================================================================
     // Zone events
@@ -89,8 +100,30 @@ pub enum EventData {
     },
     /// Zone was loaded into active simulation
     ZoneLoaded { zone_id: ZoneId },
-    /// Zone was unloaded from active simulation
+    /// Zone was unloaded from active simulation (coarse model from now on)
     ZoneUnloaded { zone_id: ZoneId },
+    /// Unloaded zone advanced by the coarse model over ticks `from`..`to`
+    /// (periodically, and once more when it is loaded again); the resulting
+    /// entity events follow
+    ZoneCoarseStepped {
+        zone_id: ZoneId,
+        from: Tick,
+        to: Tick,
+    },
+    /// Two zones became neighbours
+    ZonesLinked { a: ZoneId, b: ZoneId },
+    /// Two zones stopped being neighbours (portals between them removed)
+    ZonesUnlinked { a: ZoneId, b: ZoneId },
+    /// Portal added (or replaced) in a zone
+    PortalAdded { zone_id: ZoneId, portal: Portal },
+    /// Portal removed from a zone
+    PortalRemoved { zone_id: ZoneId, from: Position },
+
+    // ========================================================================
+    // Archetype events
+    // ========================================================================
+    /// Archetype registered (or replaced) in the world registry
+    ArchetypeDefined { archetype: Archetype },
 
     // ========================================================================
     // Entity events
@@ -101,6 +134,10 @@ pub enum EventData {
         kind: EntityKind,
         position: WorldPos,
         properties: EntityProperties,
+        #[serde(default)]
+        components: Components,
+        #[serde(default)]
+        expires_at: Option<Tick>,
     },
     /// Entity was despawned (removed)
     EntityDespawned {
@@ -126,6 +163,60 @@ pub enum EventData {
         old_value: PropertyValue,
         new_value: PropertyValue,
     },
+    /// Entity expiry tick set or cleared
+    EntityExpiryChanged {
+        entity_id: EntityId,
+        old_expires_at: Option<Tick>,
+        new_expires_at: Option<Tick>,
+    },
+    /// Entity attached to a parent (it now shares the parent's position)
+    EntityAttached {
+        entity_id: EntityId,
+        attachment: Attachment,
+        /// Parent it was attached to before (None = it was loose)
+        old_parent: Option<EntityId>,
+    },
+    /// Entity detached from its parent (it stays where it is)
+    EntityDetached {
+        entity_id: EntityId,
+        parent_id: EntityId,
+    },
+
+    // ========================================================================
+    // Timer events
+    // ========================================================================
+    /// A command was scheduled for a future tick
+    TimerScheduled {
+        timer_id: TimerId,
+        fire_at: Tick,
+        repeat_every: Option<u64>,
+        command: Command,
+    },
+    /// A timer fired (its command's own events follow)
+    TimerFired {
+        timer_id: TimerId,
+        /// Next firing tick for repeating timers (None = timer removed)
+        next_fire: Option<Tick>,
+    },
+    /// A timer was cancelled before firing
+    TimerCancelled { timer_id: TimerId },
+
+    // ========================================================================
+    // Input events
+    // ========================================================================
+    /// An external input was queued for a future tick
+    InputQueued {
+        source: SourceId,
+        seq: u64,
+        at: Tick,
+        command: Command,
+    },
+    /// A queued input reached its tick (its command's own events follow)
+    InputApplied {
+        source: SourceId,
+        seq: u64,
+        at: Tick,
+    },
 
     // ========================================================================
     // Systemic rule events (Phase 1: minimal rules)
@@ -144,6 +235,96 @@ pub enum EventData {
     },
 }
 
+impl EventData {
+    /// Whether the event only puts an entity to sleep or wakes it up.
+    pub fn is_dormancy_change(&self) -> bool {
+        matches!(
+            self,
+            EventData::EntityStateChanged {
+                old_state: EntityState::Active,
+                new_state: EntityState::Dormant,
+                ..
+            } | EventData::EntityStateChanged {
+                old_state: EntityState::Dormant,
+                new_state: EntityState::Active,
+                ..
+            }
+        )
+    }
+
+    /// The entity this event is about, if any.
+    pub fn entity_id(&self) -> Option<EntityId> {
+        match self {
+            EventData::EntitySpawned { entity_id, .. }
+            | EventData::EntityDespawned { entity_id, .. }
+            | EventData::EntityMoved { entity_id, .. }
+            | EventData::EntityStateChanged { entity_id, .. }
+            | EventData::EntityPropertyChanged { entity_id, .. }
+            | EventData::EntityExpiryChanged { entity_id, .. }
+            | EventData::EntityAttached { entity_id, .. }
+            | EventData::EntityDetached { entity_id, .. }
+            | EventData::ResourceDepleted { entity_id, .. }
+            | EventData::EntityDegraded { entity_id, .. } => Some(*entity_id),
+            _ => None,
+        }
+    }
+
+    /// Zones named by the event itself (the zone of the entity an event is
+    /// about is not included unless the event carries it).
+    pub fn zone_ids(&self) -> Vec<ZoneId> {
+        match self {
+            EventData::ZoneCreated { zone_id, .. }
+            | EventData::ZoneLoaded { zone_id }
+            | EventData::ZoneUnloaded { zone_id }
+            | EventData::ZoneCoarseStepped { zone_id, .. }
+            | EventData::PortalAdded { zone_id, .. }
+            | EventData::PortalRemoved { zone_id, .. } => vec![*zone_id],
+            EventData::ZonesLinked { a, b } | EventData::ZonesUnlinked { a, b } => vec![*a, *b],
+            EventData::EntitySpawned { position, .. } => vec![position.zone],
+            EventData::EntityMoved { from, to, .. } if from.zone != to.zone => {
+                vec![from.zone, to.zone]
+            }
+            EventData::EntityMoved { to, .. } => vec![to.zone],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Name of the event type (the variant name, as in the WAL).
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            EventData::WorldCreated { .. } => "WorldCreated",
+            EventData::WorldLoaded { .. } => "WorldLoaded",
+            EventData::WorldSaved { .. } => "WorldSaved",
+            EventData::TickProcessed { .. } => "TickProcessed",
+            EventData::CatchUpDecided { .. } => "CatchUpDecided",
+            EventData::ZoneCreated { .. } => "ZoneCreated",
+            EventData::ZoneLoaded { .. } => "ZoneLoaded",
+            EventData::ZoneUnloaded { .. } => "ZoneUnloaded",
+            EventData::ZoneCoarseStepped { .. } => "ZoneCoarseStepped",
+            EventData::ZonesLinked { .. } => "ZonesLinked",
+            EventData::ZonesUnlinked { .. } => "ZonesUnlinked",
+            EventData::PortalAdded { .. } => "PortalAdded",
+            EventData::PortalRemoved { .. } => "PortalRemoved",
+            EventData::ArchetypeDefined { .. } => "ArchetypeDefined",
+            EventData::EntitySpawned { .. } => "EntitySpawned",
+            EventData::EntityDespawned { .. } => "EntityDespawned",
+            EventData::EntityMoved { .. } => "EntityMoved",
+            EventData::EntityStateChanged { .. } => "EntityStateChanged",
+            EventData::EntityPropertyChanged { .. } => "EntityPropertyChanged",
+            EventData::EntityExpiryChanged { .. } => "EntityExpiryChanged",
+            EventData::EntityAttached { .. } => "EntityAttached",
+            EventData::EntityDetached { .. } => "EntityDetached",
+            EventData::TimerScheduled { .. } => "TimerScheduled",
+            EventData::TimerFired { .. } => "TimerFired",
+            EventData::TimerCancelled { .. } => "TimerCancelled",
+            EventData::InputQueued { .. } => "InputQueued",
+            EventData::InputApplied { .. } => "InputApplied",
+            EventData::ResourceDepleted { .. } => "ResourceDepleted",
+            EventData::EntityDegraded { .. } => "EntityDegraded",
+        }
+    }
+}
+
 /// Reason for entity despawn
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DespawnReason {
@@ -155,16 +336,19 @@ pub enum DespawnReason {
     Depleted,
     /// Expired (time-based)
     Expired,
+    /// Parent despawned with `OnParentDespawn::Cascade`
+    ParentDespawned,
 }
 
 /// Generic property value for flexible property changes
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub enum PropertyValue {
     #[default]
     None,
     Int(i64),
     UInt(u64),
-    Float(f64),
+    /// Fractional value (fixed-point: no floats in the simulation)
+    Fixed(Fixed),
     Bool(bool),
     String(String),
 }
@@ -1,20 +1,29 @@
 //! # sy_api (NIV 1)
 //!
-//! Internal API definitions: commands, events, and errors.
+//! Internal API definitions: commands, queries, events, and errors.
 //! This is the stable language used within the platform.
 //!
 //! ## Modules
+//! - `archetypes`: Named entity templates
 //! - `commands`: Intentions/requests to the simulation
+//! - `components`: Typed per-entity components
 //! - `events`: Facts representing state changes
+//! - `queries`: Read-only requests with typed results
 //! - `errors`: Typed API errors
 //! - `validation`: Input validation
 
+pub mod archetypes;
 pub mod commands;
+pub mod components;
 pub mod errors;
 pub mod events;
+pub mod queries;
 pub mod validation;
 
 // Re-exports for convenience
+pub use archetypes::*;
 pub use commands::*;
+pub use components::*;
 pub use errors::*;
 pub use events::*;
+pub use queries::*;
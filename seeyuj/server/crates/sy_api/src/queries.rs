@@ -0,0 +1,158 @@
+//! # Queries
+//!
+//! Read-only requests over a world, with typed results.
+//! Queries are "what the caller wants to know": they never change the world
+//! and produce no events.
+//!
+//! ## Design
+//! - Run by `sy_core` against any `World`: the one of a live `Simulation`
+//!   or one loaded offline from a snapshot
+//! - Results are ordered by ID, so the same world always answers the same
+//! - Lists are paginated with a cursor (the last ID of a page): a page
+//!   starts right after its cursor, whatever changed before it
+
+use serde::{Deserialize, Serialize};
+use sy_types::{
+    Attachment, EntityId, EntityKind, EntityState, Portal, Position, Tick, WorldPos, ZoneId,
+};
+
+use crate::commands::EntityProperties;
+use crate::components::Components;
+
+/// Queries that can be run against a world.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Query {
+    /// One entity by ID
+    Entity(EntityId),
+    /// Entities matching a filter, by EntityId
+    Entities { filter: EntityFilter, page: Page },
+    /// Number of entities matching a filter
+    CountEntities(EntityFilter),
+    /// One zone summary by ID
+    Zone(ZoneId),
+    /// Zone summaries, by ZoneId
+    Zones(Page),
+}
+
+/// Entity selection. Criteria left empty match everything; an entity must
+/// match all the others.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityFilter {
+    #[serde(default)]
+    pub kind: Option<EntityKind>,
+    #[serde(default)]
+    pub zone: Option<ZoneId>,
+    #[serde(default)]
+    pub state: Option<EntityState>,
+    /// Numeric property ranges (all must hold)
+    #[serde(default)]
+    pub properties: Vec<PropertyRange>,
+    /// Spatial bounds within `zone` (requires `zone`)
+    #[serde(default)]
+    pub bounds: Option<Bounds>,
+}
+
+/// Inclusive range over a numeric entity property ("amount", "health").
+/// Entities without the property never match.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PropertyRange {
+    pub property: String,
+    #[serde(default)]
+    pub min: Option<u64>,
+    #[serde(default)]
+    pub max: Option<u64>,
+}
+
+/// Inclusive axis-aligned box of grid cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bounds {
+    pub min: Position,
+    pub max: Position,
+}
+
+/// Position in a paginated list: the ID of the last item already seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Cursor(pub u64);
+
+/// Which page of a list to return.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Page {
+    /// Start after this cursor (None = from the start)
+    #[serde(default)]
+    pub after: Option<Cursor>,
+    /// Maximum number of items (1..=`Page::MAX_LIMIT`)
+    pub limit: u32,
+}
+
+impl Page {
+    pub const DEFAULT_LIMIT: u32 = 100;
+    pub const MAX_LIMIT: u32 = 10_000;
+
+    /// First page of `limit` items.
+    pub fn first(limit: u32) -> Self {
+        Page { after: None, limit }
+    }
+
+    /// Page of `limit` items after `cursor`.
+    pub fn after(cursor: Cursor, limit: u32) -> Self {
+        Page {
+            after: Some(cursor),
+            limit,
+        }
+    }
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Page::first(Page::DEFAULT_LIMIT)
+    }
+}
+
+/// A page of results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Paged<T> {
+    pub items: Vec<T>,
+    /// Cursor of the next page (None = this is the last one)
+    pub next: Option<Cursor>,
+}
+
+/// An entity as seen by a query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityView {
+    pub id: EntityId,
+    pub kind: EntityKind,
+    pub state: EntityState,
+    pub position: WorldPos,
+    pub created_at: Tick,
+    pub last_changed: Tick,
+    pub properties: EntityProperties,
+    pub components: Components,
+    pub expires_at: Option<Tick>,
+    pub attachment: Option<Attachment>,
+}
+
+/// Summary of a zone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZoneView {
+    pub id: ZoneId,
+    pub name: Option<String>,
+    pub loaded: bool,
+    /// Entities in the zone, whatever their state
+    pub entity_count: u64,
+    /// Active entities in the zone
+    pub active_count: u64,
+    /// Neighbour zones, by ZoneId
+    pub neighbours: Vec<ZoneId>,
+    pub portals: Vec<Portal>,
+}
+
+/// Result of a query (one variant per `Query` variant).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum QueryResult {
+    Entity(Option<EntityView>),
+    Entities(Paged<EntityView>),
+    Count(u64),
+    Zone(Option<ZoneView>),
+    Zones(Paged<ZoneView>),
+}
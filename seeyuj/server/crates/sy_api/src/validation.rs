@@ -2,15 +2,42 @@
 //!
 //! Input sanitization and compatibility/versioning logic.
 
-use crate::commands::{Command, CreateWorldCmd, CreateZoneCmd, SpawnEntityCmd};
+use crate::archetypes::Archetype;
+use crate::commands::{
+    AddPortalCmd, AttachEntityCmd, CatchUpCmd, Command, CreateWorldCmd, CreateZoneCmd,
+    EntityProperties, LinkZonesCmd, MoveEntityCmd, QueueInputCmd, ScheduleCmd, SetEntityExpiryCmd,
+    SetEntityPropertyCmd, SpawnEntityCmd, SpawnFromArchetypeCmd,
+};
 use crate::errors::ValidationError;
+use crate::events::PropertyValue;
+use crate::queries::{EntityFilter, Page, Query};
+use sy_types::Tick;
 
 /// Validate a command before processing
 pub fn validate_command(cmd: &Command) -> Result<(), Vec<ValidationError>> {
     let errors = match cmd {
         Command::CreateWorld(c) => validate_create_world(c),
         Command::SpawnEntity(c) => validate_spawn_entity(c),
+        Command::SpawnFromArchetype(c) => validate_spawn_from_archetype(c),
+        Command::DefineArchetype(a) => validate_define_archetype(a),
         Command::CreateZone(c) => validate_create_zone(c),
+        Command::MoveEntity(c) => validate_move_entity(c),
+        Command::SetEntityProperty(c) => validate_set_entity_property(c),
+        Command::SetEntityExpiry(c) => validate_set_entity_expiry(c),
+        Command::AttachEntity(c) => validate_attach_entity(c),
+        Command::ScheduleCommand(c) => validate_schedule(c),
+        Command::QueueInput(c) => validate_queue_input(c),
+        Command::CatchUp(c) => validate_catch_up(c),
+        Command::LinkZones(c) | Command::UnlinkZones(c) => validate_link_zones(c),
+        Command::AddPortal(c) => validate_add_portal(c),
+        Command::Batch(commands) => validate_batch(commands),
+        Command::SleepEntity(id) | Command::WakeEntity(id) | Command::DetachEntity(id) => {
+            if id.is_valid() {
+                vec![]
+            } else {
+                vec![ValidationError::new("entity_id", "Invalid entity ID")]
+            }
+        }
         Command::TickN(n) => {
             if *n == 0 {
                 vec![ValidationError::new("n", "Tick count must be > 0")]
@@ -34,6 +61,77 @@ pub fn validate_command(cmd: &Command) -> Result<(), Vec<ValidationError>> {
     }
 }
 
+/// Validate a query before running it
+pub fn validate_query(query: &Query) -> Result<(), Vec<ValidationError>> {
+    let errors = match query {
+        Query::Entity(id) => {
+            if id.is_valid() {
+                vec![]
+            } else {
+                vec![ValidationError::new("entity_id", "Invalid entity ID")]
+            }
+        }
+        Query::Entities { filter, page } => {
+            let mut errors = validate_entity_filter(filter);
+            errors.extend(validate_page(page));
+            errors
+        }
+        Query::CountEntities(filter) => validate_entity_filter(filter),
+        Query::Zones(page) => validate_page(page),
+        Query::Zone(_) => vec![],
+    };
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn validate_entity_filter(filter: &EntityFilter) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    for range in &filter.properties {
+        if EntityProperties::check(&range.property, &PropertyValue::UInt(0)).is_err() {
+            errors.push(ValidationError::new(
+                "properties",
+                format!("'{}' is not a numeric property", range.property),
+            ));
+        }
+        if range.min.zip(range.max).is_some_and(|(min, max)| min > max) {
+            errors.push(ValidationError::new(
+                "properties",
+                format!("Empty range for '{}' (min > max)", range.property),
+            ));
+        }
+    }
+    if let Some(bounds) = filter.bounds {
+        if filter.zone.is_none() {
+            errors.push(ValidationError::new(
+                "bounds",
+                "Spatial bounds require a zone",
+            ));
+        }
+        if bounds.min.x > bounds.max.x || bounds.min.y > bounds.max.y || bounds.min.z > bounds.max.z
+        {
+            errors.push(ValidationError::new("bounds", "Empty bounds (min > max)"));
+        }
+    }
+
+    errors
+}
+
+fn validate_page(page: &Page) -> Vec<ValidationError> {
+    if page.limit == 0 || page.limit > Page::MAX_LIMIT {
+        vec![ValidationError::new(
+            "limit",
+            format!("Page limit must be in 1..={}", Page::MAX_LIMIT),
+        )]
+    } else {
+        vec![]
+    }
+}
+
 fn validate_create_world(cmd: &CreateWorldCmd) -> Vec<ValidationError> {
     let mut errors = Vec::new();
 
@@ -50,9 +148,197 @@ fn validate_create_world(cmd: &CreateWorldCmd) -> Vec<ValidationError> {
     errors
 }
 
-fn validate_spawn_entity(_cmd: &SpawnEntityCmd) -> Vec<ValidationError> {
-    // Basic validation - can be extended later
-    Vec::new()
+fn validate_spawn_entity(cmd: &SpawnEntityCmd) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if let Err(e) = cmd.components.check() {
+        errors.push(ValidationError::new("components", e));
+    }
+    if cmd.expires_at == Some(Tick::ZERO) {
+        errors.push(ValidationError::new(
+            "expires_at",
+            "Expiry tick must be in the future",
+        ));
+    }
+
+    errors
+}
+
+fn validate_spawn_from_archetype(cmd: &SpawnFromArchetypeCmd) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if cmd.archetype.is_empty() {
+        errors.push(ValidationError::new(
+            "archetype",
+            "Archetype name cannot be empty",
+        ));
+    }
+    if cmd.expires_at == Some(Tick::ZERO) {
+        errors.push(ValidationError::new(
+            "expires_at",
+            "Expiry tick must be in the future",
+        ));
+    }
+
+    errors
+}
+
+fn validate_define_archetype(archetype: &Archetype) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if archetype.name.is_empty() {
+        errors.push(ValidationError::new(
+            "name",
+            "Archetype name cannot be empty",
+        ));
+    }
+    if let Err(e) = archetype.components.check() {
+        errors.push(ValidationError::new("components", e));
+    }
+
+    errors
+}
+
+fn validate_move_entity(cmd: &MoveEntityCmd) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if !cmd.entity_id.is_valid() {
+        errors.push(ValidationError::new("entity_id", "Invalid entity ID"));
+    }
+
+    errors
+}
+
+fn validate_set_entity_property(cmd: &SetEntityPropertyCmd) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if !cmd.entity_id.is_valid() {
+        errors.push(ValidationError::new("entity_id", "Invalid entity ID"));
+    }
+    if let Err(message) = EntityProperties::check(&cmd.property, &cmd.value) {
+        errors.push(ValidationError::new("value", message));
+    }
+
+    errors
+}
+
+fn validate_set_entity_expiry(cmd: &SetEntityExpiryCmd) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if !cmd.entity_id.is_valid() {
+        errors.push(ValidationError::new("entity_id", "Invalid entity ID"));
+    }
+    if cmd.expires_at == Some(Tick::ZERO) {
+        errors.push(ValidationError::new(
+            "expires_at",
+            "Expiry tick must be in the future",
+        ));
+    }
+
+    errors
+}
+
+fn validate_attach_entity(cmd: &AttachEntityCmd) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if !cmd.entity_id.is_valid() {
+        errors.push(ValidationError::new("entity_id", "Invalid entity ID"));
+    }
+    if !cmd.parent_id.is_valid() {
+        errors.push(ValidationError::new("parent_id", "Invalid entity ID"));
+    }
+    if cmd.entity_id == cmd.parent_id {
+        errors.push(ValidationError::new(
+            "parent_id",
+            "An entity cannot be attached to itself",
+        ));
+    }
+
+    errors
+}
+
+fn validate_schedule(cmd: &ScheduleCmd) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if cmd.repeat_every == Some(0) {
+        errors.push(ValidationError::new(
+            "repeat_every",
+            "Repeat period must be > 0",
+        ));
+    }
+    if !cmd.command.is_schedulable() {
+        errors.push(ValidationError::new(
+            "command",
+            "This command cannot be scheduled",
+        ));
+    } else if let Err(inner) = validate_command(&cmd.command) {
+        errors.extend(
+            inner
+                .into_iter()
+                .map(|e| ValidationError::new(format!("command.{}", e.field), e.message)),
+        );
+    }
+
+    errors
+}
+
+fn validate_queue_input(cmd: &QueueInputCmd) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if cmd.at == Tick::ZERO {
+        errors.push(ValidationError::new("at", "Input tick must be > 0"));
+    }
+    if !cmd.command.is_schedulable() {
+        errors.push(ValidationError::new(
+            "command",
+            "This command cannot be queued as an input",
+        ));
+    } else if let Err(inner) = validate_command(&cmd.command) {
+        errors.extend(
+            inner
+                .into_iter()
+                .map(|e| ValidationError::new(format!("command.{}", e.field), e.message)),
+        );
+    }
+
+    errors
+}
+
+fn validate_catch_up(cmd: &CatchUpCmd) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if cmd.ticks > cmd.missed_ticks {
+        errors.push(ValidationError::new(
+            "ticks",
+            "Cannot catch up more ticks than were missed",
+        ));
+    }
+
+    errors
+}
+
+fn validate_batch(commands: &[Command]) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if commands.is_empty() {
+        errors.push(ValidationError::new("commands", "Batch cannot be empty"));
+    }
+    for (i, command) in commands.iter().enumerate() {
+        if !command.is_batchable() {
+            errors.push(ValidationError::new(
+                format!("commands[{}]", i),
+                "This command cannot run in a batch",
+            ));
+        } else if let Err(inner) = validate_command(command) {
+            errors.extend(
+                inner.into_iter().map(|e| {
+                    ValidationError::new(format!("commands[{}].{}", i, e.field), e.message)
+                }),
+            );
+        }
+    }
+
+    errors
 }
 
 fn validate_create_zone(cmd: &CreateZoneCmd) -> Vec<ValidationError> {
@@ -70,6 +356,32 @@ fn validate_create_zone(cmd: &CreateZoneCmd) -> Vec<ValidationError> {
     errors
 }
 
+fn validate_link_zones(cmd: &LinkZonesCmd) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if cmd.a == cmd.b {
+        errors.push(ValidationError::new(
+            "b",
+            "A zone cannot be its own neighbour",
+        ));
+    }
+
+    errors
+}
+
+fn validate_add_portal(cmd: &AddPortalCmd) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if cmd.portal.to.zone == cmd.zone_id {
+        errors.push(ValidationError::new(
+            "portal.to",
+            "Portal must lead to another zone",
+        ));
+    }
+
+    errors
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,6 +397,136 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn validate_property_type() {
+        use crate::events::PropertyValue;
+        use sy_types::EntityId;
+
+        let set = |property: &str, value| {
+            validate_command(&Command::SetEntityProperty(SetEntityPropertyCmd {
+                entity_id: EntityId::new(1),
+                property: property.to_string(),
+                value,
+            }))
+        };
+
+        assert!(set("health", PropertyValue::UInt(10)).is_ok());
+        assert!(set("name", PropertyValue::None).is_ok());
+        assert!(set("health", PropertyValue::String("x".into())).is_err());
+        assert!(set("amount", PropertyValue::UInt(u64::MAX)).is_err());
+        assert!(set("speed", PropertyValue::UInt(1)).is_err());
+    }
+
+    #[test]
+    fn validate_float_free_components() {
+        use crate::components::Components;
+        use sy_types::WorldPos;
+
+        let components: Components = serde_json::from_str(r#"{ "speed": 1.5 }"#).unwrap();
+        let errors = validate_command(&Command::SpawnEntity(SpawnEntityCmd {
+            position: WorldPos::origin(),
+            kind: sy_types::EntityKind::Item,
+            properties: EntityProperties::default(),
+            components: components.clone(),
+            expires_at: None,
+        }))
+        .unwrap_err();
+        assert_eq!(errors[0].field, "components");
+
+        assert!(validate_command(&Command::DefineArchetype(Archetype {
+            name: "fast".to_string(),
+            kind: sy_types::EntityKind::Item,
+            properties: EntityProperties::default(),
+            components,
+            tags: Default::default(),
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn validate_schedule_rejects_lifecycle_commands() {
+        let schedule = |command| {
+            validate_command(&Command::ScheduleCommand(ScheduleCmd {
+                at: Tick(10),
+                repeat_every: None,
+                command: Box::new(command),
+            }))
+        };
+
+        assert!(schedule(Command::LoadZone(sy_types::ZoneId::ORIGIN)).is_ok());
+        assert!(schedule(Command::Tick).is_err());
+        assert!(schedule(Command::SaveWorld).is_err());
+        assert!(schedule(Command::TickN(0)).is_err());
+    }
+
+    #[test]
+    fn validate_batch_steps() {
+        assert!(validate_command(&Command::Batch(vec![])).is_err());
+        assert!(validate_command(&Command::Batch(vec![Command::SaveWorld])).is_err());
+
+        let errors =
+            validate_command(&Command::Batch(vec![Command::Tick, Command::TickN(0)])).unwrap_err();
+        assert_eq!(errors[0].field, "commands[1].n");
+    }
+
+    #[test]
+    fn validate_catch_up_within_missed_ticks() {
+        let catch_up = |missed_ticks, ticks| {
+            validate_command(&Command::CatchUp(CatchUpCmd {
+                offline_ms: 1000,
+                missed_ticks,
+                ticks,
+            }))
+        };
+
+        assert!(catch_up(20, 20).is_ok());
+        assert!(catch_up(20, 5).is_ok());
+        assert!(catch_up(20, 21).is_err());
+    }
+
+    #[test]
+    fn validate_query_filters_and_pages() {
+        use crate::queries::{Bounds, PropertyRange};
+        use sy_types::{Position, ZoneId};
+
+        let entities = |filter: EntityFilter, limit| {
+            validate_query(&Query::Entities {
+                filter,
+                page: Page::first(limit),
+            })
+        };
+        let range = |property: &str, min, max| PropertyRange {
+            property: property.to_string(),
+            min,
+            max,
+        };
+        let bounds = Bounds {
+            min: Position::ORIGIN,
+            max: Position::new(10, 10, 0),
+        };
+
+        assert!(entities(EntityFilter::default(), 100).is_ok());
+        assert!(entities(EntityFilter::default(), 0).is_err());
+        assert!(entities(EntityFilter::default(), Page::MAX_LIMIT + 1).is_err());
+
+        let mut filter = EntityFilter {
+            properties: vec![range("amount", Some(5), None)],
+            ..Default::default()
+        };
+        assert!(entities(filter.clone(), 10).is_ok());
+        filter.properties.push(range("name", None, Some(3)));
+        filter.properties.push(range("health", Some(4), Some(3)));
+        assert_eq!(entities(filter, 10).unwrap_err().len(), 2);
+
+        let mut filter = EntityFilter {
+            bounds: Some(bounds),
+            ..Default::default()
+        };
+        assert!(validate_query(&Query::CountEntities(filter.clone())).is_err());
+        filter.zone = Some(ZoneId::ORIGIN);
+        assert!(validate_query(&Query::CountEntities(filter)).is_ok());
+    }
+
     #[test]
     fn validate_tick_zero() {
         let result = validate_command(&Command::TickN(0));
@@ -16,7 +16,8 @@ use byteorder::{LittleEndian, WriteBytesExt};
 use xxhash_rust::xxh64::Xxh64;
 
 use sy_api::commands::Command;
-use sy_types::{RngSeed, Tick};
+use sy_api::components::Components;
+use sy_types::{OnParentDespawn, RngSeed, Tick};
 
 use crate::ports::{IEventLog, IRng, ISimClock, IStateHasher, IWorldStore, StateHash};
 use crate::world::World;
@@ -67,22 +68,42 @@ impl IStateHasher for XxHasher {
 /// Compute a canonical hash of the world state.
 ///
 /// ## Canonical Encoding
-/// The hash covers (in order):
+/// The hash covers (in order; lengths are u32 LE, strings UTF-8 bytes):
 /// 1. Tick (u64 LE)
 /// 2. SimTime (u64 LE)
 /// 3. RNG state (u64 LE)
-/// 4. Number of entities (u64 LE)
-/// 5. For each entity (sorted by EntityId):
+/// 4. Next entity ID (u64 LE)
+/// 5. Number of entities (u64 LE)
+/// 6. For each entity (sorted by EntityId):
 ///    - EntityId (u64 LE)
-///    - Kind (u8)
-///    - State (u8)
+///    - Kind (u8: 0 resource, 1 creature, 2 item, 3 structure, 255 other)
+///    - State (u8: 0 active, 1 dormant, 2 dead)
 ///    - Position: zone (u32 LE), x (i32 LE), y (i32 LE), z (i32 LE)
-///    - Properties: name length + bytes, amount (u32 LE), health (u32 LE)
-/// 6. Number of zones (u64 LE)
-/// 7. For each zone (sorted by ZoneId):
+///    - created_at (u64 LE), last_changed (u64 LE), last_active (u64 LE),
+///      expires_at (u64 LE, 0 = never)
+///    - Parent (u64 LE, 0 = loose), on_parent_despawn (u8: 0 orphan or
+///      loose, 1 cascade)
+///    - Properties: name length + bytes (length 0 = none), amount (u32 LE,
+///      0 = none), health (u32 LE, 0 = none)
+///    - Components: count (u64 LE), then for each (sorted by name):
+///      name length + bytes, canonical value length + bytes
+/// 7. Number of zones (u64 LE)
+/// 8. For each zone (sorted by ZoneId):
 ///    - ZoneId (u32 LE)
-///    - loaded (u8)
+///    - loaded (u8: 1 loaded), simulated_until (u64 LE)
 ///    - Number of entities in zone (u64 LE)
+///    - Neighbours: count (u64 LE), then each ZoneId (u32 LE, sorted)
+///    - Portals: count (u64 LE), then for each (sorted by entrance):
+///      from x, y, z (i32 LE), to zone (u32 LE), to x, y, z (i32 LE)
+/// 9. Timers: next timer ID (u64 LE), count (u64 LE), then for each
+///    timer (sorted by TimerId): id (u64 LE), fire_at (u64 LE),
+///    repeat_every (u64 LE, 0 = one-shot), command length + JSON bytes
+/// 10. Inputs: source count (u64 LE), then for each source (sorted):
+///     SourceId (u32 LE), last seq (u64 LE); pending count (u64 LE), then
+///     for each input (sorted by tick, source, seq): at (u64 LE),
+///     source (u32 LE), seq (u64 LE), command length + JSON bytes
+/// 11. Archetypes: count (u64 LE), then for each (sorted by name):
+///     definition length + JSON bytes
 ///
 /// This encoding is stable across runs.
 pub fn compute_canonical_hash(world: &World, hasher: &mut dyn IStateHasher) -> StateHash {
@@ -139,9 +160,29 @@ pub fn compute_canonical_hash(world: &World, hasher: &mut dyn IStateHasher) -> S
         buf.write_i32::<LittleEndian>(entity.position.pos.z)
             .unwrap();
 
-        // Created at
+        // Created at / last changed
         buf.write_u64::<LittleEndian>(entity.created_at.as_u64())
             .unwrap();
+        buf.write_u64::<LittleEndian>(entity.last_changed.as_u64())
+            .unwrap();
+        buf.write_u64::<LittleEndian>(entity.last_active.as_u64())
+            .unwrap();
+        buf.write_u64::<LittleEndian>(entity.expires_at.map_or(0, |t| t.as_u64()))
+            .unwrap();
+
+        // Hierarchy
+        let (parent, policy) = match entity.attachment {
+            Some(a) => (
+                a.parent.as_u64(),
+                match a.on_parent_despawn {
+                    OnParentDespawn::Orphan => 0u8,
+                    OnParentDespawn::Cascade => 1u8,
+                },
+            ),
+            None => (0, 0),
+        };
+        buf.write_u64::<LittleEndian>(parent).unwrap();
+        buf.write_u8(policy).unwrap();
 
         // Properties
         if let Some(ref name) = entity.properties.name {
@@ -154,6 +195,17 @@ pub fn compute_canonical_hash(world: &World, hasher: &mut dyn IStateHasher) -> S
             .unwrap();
         buf.write_u32::<LittleEndian>(entity.properties.health.unwrap_or(0))
             .unwrap();
+
+        // Components (BTreeMap guarantees sorted order by name)
+        buf.write_u64::<LittleEndian>(entity.components.len() as u64)
+            .unwrap();
+        for (name, value) in entity.components.iter() {
+            buf.write_u32::<LittleEndian>(name.len() as u32).unwrap();
+            buf.extend_from_slice(name.as_bytes());
+            let bytes = Components::canonical_bytes(value);
+            buf.write_u32::<LittleEndian>(bytes.len() as u32).unwrap();
+            buf.extend_from_slice(&bytes);
+        }
     }
 
     // 7. Number of zones
@@ -164,8 +216,74 @@ pub fn compute_canonical_hash(world: &World, hasher: &mut dyn IStateHasher) -> S
     for (id, zone) in &world.zones {
         buf.write_u32::<LittleEndian>(id.as_u32()).unwrap();
         buf.push(if zone.loaded { 1u8 } else { 0u8 });
+        buf.write_u64::<LittleEndian>(zone.simulated_until.as_u64())
+            .unwrap();
         buf.write_u64::<LittleEndian>(zone.entities.len() as u64)
             .unwrap();
+
+        buf.write_u64::<LittleEndian>(zone.neighbours.len() as u64)
+            .unwrap();
+        for neighbour in &zone.neighbours {
+            buf.write_u32::<LittleEndian>(neighbour.as_u32()).unwrap();
+        }
+
+        buf.write_u64::<LittleEndian>(zone.portals.len() as u64)
+            .unwrap();
+        for portal in &zone.portals {
+            for v in [portal.from.x, portal.from.y, portal.from.z] {
+                buf.write_i32::<LittleEndian>(v).unwrap();
+            }
+            buf.write_u32::<LittleEndian>(portal.to.zone.as_u32())
+                .unwrap();
+            for v in [portal.to.pos.x, portal.to.pos.y, portal.to.pos.z] {
+                buf.write_i32::<LittleEndian>(v).unwrap();
+            }
+        }
+    }
+
+    // 9. Timers (BTreeMap guarantees sorted order by TimerId)
+    buf.write_u64::<LittleEndian>(world.timers.next_id())
+        .unwrap();
+    buf.write_u64::<LittleEndian>(world.timers.len() as u64)
+        .unwrap();
+    for timer in world.timers.iter() {
+        buf.write_u64::<LittleEndian>(timer.id.as_u64()).unwrap();
+        buf.write_u64::<LittleEndian>(timer.fire_at.as_u64())
+            .unwrap();
+        buf.write_u64::<LittleEndian>(timer.repeat_every.unwrap_or(0))
+            .unwrap();
+        let command = serde_json::to_vec(&timer.command).unwrap_or_default();
+        buf.write_u32::<LittleEndian>(command.len() as u32).unwrap();
+        buf.extend_from_slice(&command);
+    }
+
+    // 10. Inputs (sources by SourceId, pending by tick, source, seq)
+    let sources: Vec<_> = world.inputs.sources().collect();
+    buf.write_u64::<LittleEndian>(sources.len() as u64).unwrap();
+    for (source, seq) in sources {
+        buf.write_u32::<LittleEndian>(source.as_u32()).unwrap();
+        buf.write_u64::<LittleEndian>(seq).unwrap();
+    }
+    buf.write_u64::<LittleEndian>(world.inputs.len() as u64)
+        .unwrap();
+    for input in world.inputs.iter() {
+        buf.write_u64::<LittleEndian>(input.at.as_u64()).unwrap();
+        buf.write_u32::<LittleEndian>(input.source.as_u32())
+            .unwrap();
+        buf.write_u64::<LittleEndian>(input.seq).unwrap();
+        let command = serde_json::to_vec(&input.command).unwrap_or_default();
+        buf.write_u32::<LittleEndian>(command.len() as u32).unwrap();
+        buf.extend_from_slice(&command);
+    }
+
+    // 11. Archetypes (BTreeMap guarantees sorted order by name)
+    buf.write_u64::<LittleEndian>(world.archetypes.len() as u64)
+        .unwrap();
+    for archetype in world.archetypes.values() {
+        let definition = serde_json::to_vec(archetype).unwrap_or_default();
+        buf.write_u32::<LittleEndian>(definition.len() as u32)
+            .unwrap();
+        buf.extend_from_slice(&definition);
     }
 
     hasher.update(&buf);
@@ -208,6 +326,8 @@ pub struct DeterministicRunConfig {
     pub total_ticks: u64,
     /// Checkpoint interval (0 = only final)
     pub checkpoint_every: u64,
+    /// Number of threads used to tick zones (the hashes must not depend on it)
+    pub tick_threads: usize,
 }
 
 /// Result of a deterministic run.
@@ -243,6 +363,7 @@ where
     S: IWorldStore,
 {
     let mut sim = Simulation::new(rng, clock, event_log, store);
+    sim.set_tick_threads(config.tick_threads);
     let mut hasher = XxHasher::new();
     let mut checkpoints = Vec::new();
 
@@ -322,7 +443,7 @@ pub fn verify_determinism(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ports::{IEventLog, IRng, ISimClock, IWorldStore};
+    use crate::ports::{stream_key, IEventLog, IRng, ISimClock, IWorldStore};
     use std::collections::HashMap;
     use sy_api::commands::{EntityProperties, SpawnEntityCmd};
     use sy_api::events::SimEvent;
@@ -357,6 +478,12 @@ mod tests {
             self.state = state;
         }
 
+        fn split(&self, stream: u64) -> Box<dyn IRng> {
+            Box::new(TestRng::new(RngSeed::new(stream_key(&[
+                self.state, stream,
+            ]))))
+        }
+
         fn next_u32(&mut self) -> u32 {
             self.next_u64() as u32
         }
@@ -552,6 +679,8 @@ mod tests {
                         amount: Some(100),
                         health: None,
                     },
+                    components: Components::default(),
+                    expires_at: None,
                 }),
             });
         }
@@ -568,6 +697,8 @@ mod tests {
                         amount: None,
                         health: Some(100),
                     },
+                    components: Components::default(),
+                    expires_at: None,
                 }),
             });
         }
@@ -588,6 +719,7 @@ mod tests {
             inputs,
             total_ticks: steps,
             checkpoint_every,
+            tick_threads: 1,
         };
 
         // Run A
@@ -637,6 +769,7 @@ mod tests {
             inputs,
             total_ticks: steps,
             checkpoint_every,
+            tick_threads: 1,
         };
 
         let result_a = run_deterministic(
@@ -675,6 +808,7 @@ mod tests {
             inputs: inputs.clone(),
             total_ticks: steps,
             checkpoint_every,
+            tick_threads: 1,
         };
 
         let config_b = DeterministicRunConfig {
@@ -683,6 +817,7 @@ mod tests {
             inputs,
             total_ticks: steps,
             checkpoint_every,
+            tick_threads: 1,
         };
 
         let result_a = run_deterministic(
@@ -709,6 +844,105 @@ mod tests {
         );
     }
 
+    /// Several zones full of entities, plus a walker crossing a portal.
+    fn multi_zone_input_stream() -> Vec<ScheduledCommand> {
+        use sy_api::commands::{AddPortalCmd, CreateZoneCmd};
+        use sy_api::components::Velocity;
+        use sy_types::Portal;
+
+        let at = |tick, command| ScheduledCommand {
+            tick: Tick(tick),
+            command,
+        };
+        let spawn = |zone: u32, x: i32, kind, amount, health, components| {
+            Command::SpawnEntity(SpawnEntityCmd {
+                position: WorldPos::new(ZoneId::new(zone), Position::new(x, 0, 0)),
+                kind,
+                properties: EntityProperties {
+                    name: None,
+                    amount,
+                    health,
+                },
+                components,
+                expires_at: None,
+            })
+        };
+
+        let mut inputs = Vec::new();
+        for zone in 1..=6 {
+            inputs.push(at(
+                0,
+                Command::CreateZone(CreateZoneCmd {
+                    zone_id: ZoneId::new(zone),
+                    name: None,
+                }),
+            ));
+            for i in 0..20 {
+                let components = Components::default();
+                inputs.push(at(
+                    0,
+                    spawn(zone, i, EntityKind::Resource, Some(100), None, components),
+                ));
+                let components = Components::default();
+                inputs.push(at(
+                    0,
+                    spawn(zone, i, EntityKind::Creature, None, Some(50), components),
+                ));
+            }
+        }
+        inputs.push(at(
+            0,
+            Command::AddPortal(AddPortalCmd {
+                zone_id: ZoneId::new(1),
+                portal: Portal {
+                    from: Position::new(5, 0, 0),
+                    to: WorldPos::new(ZoneId::new(2), Position::new(100, 0, 0)),
+                },
+            }),
+        ));
+        let walker = Components::new()
+            .with(&Velocity {
+                dx: 1,
+                dy: 0,
+                dz: 0,
+            })
+            .unwrap();
+        inputs.push(at(
+            2,
+            spawn(1, 0, EntityKind::Creature, None, Some(50), walker),
+        ));
+
+        inputs
+    }
+
+    #[test]
+    fn parallel_tick_matches_single_thread() {
+        let seed = RngSeed::new(7);
+        let run = |tick_threads| {
+            let config = DeterministicRunConfig {
+                seed,
+                world_name: "Parallel".to_string(),
+                inputs: multi_zone_input_stream(),
+                total_ticks: 300,
+                checkpoint_every: 25,
+                tick_threads,
+            };
+            run_deterministic(
+                &config,
+                TestRng::new(seed),
+                TestClock::new(),
+                TestEventLog::new(),
+                TestWorldStore::new(),
+            )
+        };
+
+        let single = run(1);
+        for threads in [2, 3, 8] {
+            verify_determinism(&single, &run(threads))
+                .unwrap_or_else(|tick| panic!("{} threads diverged at {}", threads, tick));
+        }
+    }
+
     #[test]
     fn canonical_hash_is_stable() {
         let world = World::new("Hash Test".to_string(), RngSeed::new(42));
@@ -721,4 +955,30 @@ mod tests {
 
         assert_eq!(hash1, hash2, "Same world should produce same hash");
     }
+
+    #[test]
+    fn canonical_hash_covers_components() {
+        let mut world = World::new("Hash Test".to_string(), RngSeed::new(42));
+        let id = world.allocate_entity_id();
+        world.add_entity(crate::world::Entity::new(
+            id,
+            EntityKind::Creature,
+            WorldPos::origin(),
+            Tick::ZERO,
+            EntityProperties::default(),
+        ));
+
+        let mut hasher = XxHasher::new();
+        let before = compute_canonical_hash(&world, &mut hasher);
+
+        world
+            .get_entity_mut(id)
+            .unwrap()
+            .components
+            .insert_raw("mood", serde_json::json!({ "level": 3 }))
+            .unwrap();
+        let after = compute_canonical_hash(&world, &mut hasher);
+
+        assert_ne!(before, after, "Components must be part of the hash");
+    }
 }
@@ -0,0 +1,179 @@
+//! # Fork
+//!
+//! Branch a new world off a point of another world's history, to try
+//! "what if" scenarios without touching the original.
+//!
+//! ## Design
+//! - The fork starts exactly as the parent was right after the fork event:
+//!   same entities, zones, RNG root and pending timers/inputs
+//! - Its history up to that point is the parent's (its WAL keeps the
+//!   parent's events, IDs included); it gets its own world ID and records
+//!   where it came from in `WorldMeta::forked_from`
+//! - From then on the two worlds share nothing
+//! - Pure: copying and truncating the WAL, and saving the fork, are left to
+//!   the caller (see `sy_tools::fork`)
+
+use sy_api::events::SimEvent;
+use sy_types::{EventId, ForkPoint, Tick, WorldMeta};
+
+use crate::replay::rebuild;
+use crate::world::World;
+
+/// Where to fork a world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForkAt {
+    /// Right after this event
+    Event(EventId),
+    /// At the end of this tick (after every event recorded at it)
+    Tick(Tick),
+}
+
+impl ForkAt {
+    /// The last event of `events` (the whole log, in event_id order) kept by
+    /// the fork.
+    pub fn resolve(self, events: &[SimEvent]) -> Result<&SimEvent, String> {
+        match self {
+            ForkAt::Event(id) => events
+                .iter()
+                .find(|e| e.event_id == id)
+                .ok_or_else(|| format!("Event {} is not in the log", id)),
+            ForkAt::Tick(tick) => {
+                let reached = events.last().map_or(Tick::ZERO, |e| e.tick);
+                if tick > reached {
+                    return Err(format!("The world only reached {}", reached));
+                }
+                events
+                    .iter()
+                    .take_while(|e| e.tick <= tick)
+                    .last()
+                    .ok_or_else(|| format!("No event recorded at or before {}", tick))
+            }
+        }
+    }
+}
+
+/// Build the world `world_id` forked from the parent at `at`.
+///
+/// `snapshot` is the parent's latest snapshot (used if taken before the
+/// fork point) and `events` its whole log. The result is ready to be saved
+/// as the fork's snapshot: the fork's WAL must then end at
+/// `forked_from.event_id`.
+pub fn fork_world(
+    snapshot: World,
+    events: &[SimEvent],
+    at: ForkAt,
+    world_id: &str,
+) -> Result<World, String> {
+    let point = at.resolve(events)?;
+    let (last, tick) = (point.event_id, point.tick);
+    let parent = snapshot.meta.clone();
+
+    let mut world = rebuild(Some(snapshot), events, last)?;
+    world.meta = WorldMeta {
+        world_id: world_id.to_string(),
+        current_tick: world.current_tick,
+        sim_time: world.sim_time,
+        snapshot_tick: world.current_tick,
+        last_event_id: last,
+        forked_from: Some(ForkPoint {
+            parent_world_id: parent.world_id.clone(),
+            event_id: last,
+            tick,
+        }),
+        ..parent
+    };
+
+    Ok(world)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sy_api::commands::EntityProperties;
+    use sy_api::components::Components;
+    use sy_api::events::EventData;
+    use sy_types::{EntityId, EntityKind, Position, RngSeed, SimTime, WorldPos, ZoneId};
+
+    use crate::replay::state_at;
+
+    fn log() -> Vec<SimEvent> {
+        let at = WorldPos::new(ZoneId::ORIGIN, Position::ORIGIN);
+        let tick = |n| EventData::TickProcessed {
+            tick: Tick(n),
+            sim_time: SimTime::from_ticks(Tick(n)),
+            entities_processed: 0,
+        };
+        let data = vec![
+            (
+                0,
+                EventData::WorldCreated {
+                    world_id: "world_3".to_string(),
+                    name: "Parent".to_string(),
+                    seed: RngSeed::new(3),
+                },
+            ),
+            (1, tick(1)),
+            (
+                1,
+                EventData::EntitySpawned {
+                    entity_id: EntityId::new(1),
+                    kind: EntityKind::Item,
+                    position: at,
+                    properties: EntityProperties::default(),
+                    components: Components::default(),
+                    expires_at: None,
+                },
+            ),
+            (2, tick(2)),
+            (3, tick(3)),
+        ];
+        data.into_iter()
+            .enumerate()
+            .map(|(i, (t, data))| SimEvent::with_id(EventId::new(i as u64 + 1), Tick(t), data))
+            .collect()
+    }
+
+    #[test]
+    fn fork_point_by_event_or_tick() {
+        let events = log();
+        let id = |at: ForkAt| at.resolve(&events).map(|e| e.event_id);
+
+        assert_eq!(id(ForkAt::Event(EventId::new(2))), Ok(EventId::new(2)));
+        assert_eq!(id(ForkAt::Tick(Tick(1))), Ok(EventId::new(3)));
+        assert_eq!(id(ForkAt::Tick(Tick(3))), Ok(EventId::new(5)));
+        assert!(id(ForkAt::Event(EventId::new(9))).is_err());
+        assert!(id(ForkAt::Tick(Tick(4))).is_err());
+    }
+
+    #[test]
+    fn fork_records_its_parent() {
+        let events = log();
+        let snapshot = state_at(None, &events, Tick(3)).unwrap();
+
+        let fork = fork_world(snapshot, &events, ForkAt::Tick(Tick(1)), "what_if").unwrap();
+        assert_eq!(fork.id(), "what_if");
+        assert_eq!(fork.meta.name, "Parent");
+        assert_eq!(fork.current_tick, Tick(1));
+        assert_eq!(fork.meta.snapshot_tick, Tick(1));
+        assert_eq!(fork.meta.last_event_id, EventId::new(3));
+        assert_eq!(
+            fork.meta.forked_from,
+            Some(ForkPoint {
+                parent_world_id: "world_3".to_string(),
+                event_id: EventId::new(3),
+                tick: Tick(1),
+            })
+        );
+        assert!(fork.get_entity(EntityId::new(1)).is_some());
+
+        // Before the entity was spawned
+        let fork = fork_world(
+            state_at(None, &events, Tick(3)).unwrap(),
+            &events,
+            ForkAt::Event(EventId::new(2)),
+            "earlier",
+        )
+        .unwrap();
+        assert!(fork.get_entity(EntityId::new(1)).is_none());
+    }
+}
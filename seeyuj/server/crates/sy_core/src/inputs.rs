@@ -0,0 +1,170 @@
+//! # Inputs
+//!
+//! Queue of external commands waiting for their target tick.
+//!
+//! ## Design
+//! - Inputs live in `World` (serialized with the snapshot, hashed)
+//! - Each input carries its source and a sequence number that must
+//!   increase per source: a resubmitted input is rejected, not run twice
+//! - Application order is (tick, source, seq), whatever the arrival order
+//!   across sources
+//! - Every change is mirrored by an event (InputQueued / InputApplied) so
+//!   replay sees the exact same inputs at the same ticks
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use sy_api::commands::Command;
+use sy_types::{SourceId, Tick};
+
+/// An external command waiting for its tick.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedInput {
+    /// Who sent the input
+    pub source: SourceId,
+    /// Sequence number within the source
+    pub seq: u64,
+    /// Tick at which the input is applied
+    pub at: Tick,
+    /// The command to run
+    pub command: Command,
+}
+
+/// Input queue stored in the world.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InputQueue {
+    /// Highest sequence number received from each source
+    last_seq: BTreeMap<SourceId, u64>,
+    /// Pending inputs: tick -> inputs sorted by (source, seq)
+    pending: BTreeMap<Tick, Vec<QueuedInput>>,
+}
+
+impl InputQueue {
+    /// Highest sequence number received from `source`.
+    pub fn last_seq(&self, source: SourceId) -> Option<u64> {
+        self.last_seq.get(&source).copied()
+    }
+
+    /// Whether an input numbered `seq` from `source` would be new.
+    pub fn accepts(&self, source: SourceId, seq: u64) -> bool {
+        self.last_seq(source).is_none_or(|last| seq > last)
+    }
+
+    /// Queue an input (replaces any input with the same key).
+    pub fn insert(&mut self, input: QueuedInput) {
+        let last = self.last_seq.entry(input.source).or_insert(input.seq);
+        *last = (*last).max(input.seq);
+
+        let inputs = self.pending.entry(input.at).or_default();
+        match inputs.binary_search_by_key(&(input.source, input.seq), |i| (i.source, i.seq)) {
+            Ok(pos) => inputs[pos] = input,
+            Err(pos) => inputs.insert(pos, input),
+        }
+    }
+
+    /// Remove a pending input. Returns it if it existed.
+    pub fn remove(&mut self, at: Tick, source: SourceId, seq: u64) -> Option<QueuedInput> {
+        let inputs = self.pending.get_mut(&at)?;
+        let pos = inputs
+            .binary_search_by_key(&(source, seq), |i| (i.source, i.seq))
+            .ok()?;
+        let input = inputs.remove(pos);
+        if inputs.is_empty() {
+            self.pending.remove(&at);
+        }
+        Some(input)
+    }
+
+    /// Inputs due at or before `tick`, in (tick, source, seq) order.
+    pub fn due(&self, tick: Tick) -> Vec<QueuedInput> {
+        self.pending
+            .range(..=tick)
+            .flat_map(|(_, inputs)| inputs.iter().cloned())
+            .collect()
+    }
+
+    /// Iterate pending inputs in (tick, source, seq) order.
+    pub fn iter(&self) -> impl Iterator<Item = &QueuedInput> {
+        self.pending.values().flatten()
+    }
+
+    /// Iterate sources and their last sequence number, by SourceId.
+    pub fn sources(&self) -> impl Iterator<Item = (SourceId, u64)> + '_ {
+        self.last_seq.iter().map(|(s, seq)| (*s, *seq))
+    }
+
+    /// Number of pending inputs.
+    pub fn len(&self) -> usize {
+        self.pending.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(queue: &mut InputQueue, at: u64, source: u32, seq: u64) {
+        queue.insert(QueuedInput {
+            source: SourceId::new(source),
+            seq,
+            at: Tick(at),
+            command: Command::SaveWorld,
+        });
+    }
+
+    fn keys(inputs: &[QueuedInput]) -> Vec<(u64, u32, u64)> {
+        inputs
+            .iter()
+            .map(|i| (i.at.as_u64(), i.source.as_u32(), i.seq))
+            .collect()
+    }
+
+    #[test]
+    fn due_in_tick_source_seq_order() {
+        let mut queue = InputQueue::default();
+        input(&mut queue, 10, 2, 1);
+        input(&mut queue, 20, 1, 5);
+        input(&mut queue, 10, 1, 7);
+        input(&mut queue, 10, 1, 3);
+
+        assert_eq!(
+            keys(&queue.due(Tick(10))),
+            vec![(10, 1, 3), (10, 1, 7), (10, 2, 1)]
+        );
+        assert_eq!(queue.due(Tick(20)).len(), 4);
+
+        assert!(queue.remove(Tick(10), SourceId::new(1), 3).is_some());
+        assert!(queue.remove(Tick(10), SourceId::new(1), 3).is_none());
+        assert_eq!(queue.len(), 3);
+    }
+
+    #[test]
+    fn sequence_numbers_increase_per_source() {
+        let mut queue = InputQueue::default();
+        input(&mut queue, 10, 1, 4);
+
+        assert!(!queue.accepts(SourceId::new(1), 4));
+        assert!(!queue.accepts(SourceId::new(1), 2));
+        assert!(queue.accepts(SourceId::new(1), 5));
+        assert!(queue.accepts(SourceId::new(2), 0));
+
+        // Applied inputs are still remembered
+        queue.remove(Tick(10), SourceId::new(1), 4);
+        assert_eq!(queue.last_seq(SourceId::new(1)), Some(4));
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        let mut queue = InputQueue::default();
+        input(&mut queue, 3, 1, 1);
+
+        let json = serde_json::to_string(&queue).unwrap();
+        let restored: InputQueue = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.due(Tick(3)).len(), 1);
+        assert_eq!(restored.last_seq(SourceId::new(1)), Some(1));
+    }
+}
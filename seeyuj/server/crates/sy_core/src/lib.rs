@@ -15,12 +15,28 @@
 //! - `Simulation`: The engine that processes commands and runs ticks
 //! - `replay`: Event replay for crash recovery
 //! - `determinism`: Determinism verification tools
+//! - `fork`: New worlds branched off a point of another world's history
+//! - `inputs`: External commands queued for their target tick
+//! - `invariants`: World consistency checks (strict mode)
+//! - `queries`: Read-only queries over a World (live or offline)
+//! - `spatial`: Per-zone spatial index (range, box and nearest queries)
+//! - `subscriptions`: In-process bus delivering persisted events
+//! - `systems`: Pluggable tick systems (systemic rules)
+//! - `timers`: Commands scheduled at future ticks
 //! - `ports::*`: Interfaces for external dependencies
 
 pub mod determinism;
+pub mod fork;
+pub mod inputs;
+pub mod invariants;
 pub mod ports;
+pub mod queries;
 pub mod replay;
 pub mod sim;
+pub mod spatial;
+pub mod subscriptions;
+pub mod systems;
+pub mod timers;
 pub mod world;
 
 // Re-exports
@@ -28,6 +44,11 @@ pub use determinism::{
     compute_canonical_hash, run_deterministic, verify_determinism, Checkpoint,
     DeterministicRunConfig, DeterministicRunResult, ScheduledCommand, XxHasher,
 };
-pub use replay::{apply_event, replay_events};
+pub use fork::{fork_world, ForkAt};
+pub use invariants::{check_invariants, InvariantReport, Violation};
+pub use queries::run_query;
+pub use replay::{apply_event, replay_events, state_at};
 pub use sim::Simulation;
+pub use subscriptions::{EventFilter, IEventSubscriber, SubscriptionId};
+pub use systems::{ITickSystem, SystemId, TickContext};
 pub use world::{Entity, World, Zone};
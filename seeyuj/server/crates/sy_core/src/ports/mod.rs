@@ -20,6 +20,6 @@ pub mod store;
 // Re-exports
 pub use event_log::IEventLog;
 pub use hasher::{IStateHasher, StateHash};
-pub use rng::IRng;
+pub use rng::{name_key, stream_key, IRng};
 pub use sim_clock::ISimClock;
 pub use store::{IWorldStore, WorldSnapshot};
@@ -7,8 +7,34 @@
 //! - NEVER use std::rand or any non-injected RNG
 //! - RNG state must be serializable for replay
 //! - Same seed + same sequence of calls = same results
+//! - No floating point: probabilities are integer ratios (`Probability`)
+//!   and fractions are fixed-point (`Fixed`), so draws are bit-identical on
+//!   every target
+//!
+//! ## Streams
+//! Simulation rules never share one sequence: they draw from streams split
+//! off the world root by key (system ID, entity ID, tick), so adding an
+//! entity or a rule does not shift the numbers seen by the others.
+
+use sy_types::{Fixed, Probability, RngSeed};
+
+/// Mix values into a well-distributed stream key (SplitMix64 finalizer).
+/// Used to derive sub-stream seeds: `stream_key(&[state, stream])`.
+pub fn stream_key(parts: &[u64]) -> u64 {
+    parts.iter().fold(0x9E37_79B9_7F4A_7C15, |acc, &part| {
+        let mut z = (acc ^ part).wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    })
+}
 
-use sy_types::RngSeed;
+/// Stable 64-bit key of a name (FNV-1a), e.g. a system ID in a stream key.
+pub fn name_key(name: &str) -> u64 {
+    name.bytes().fold(0xCBF2_9CE4_8422_2325, |acc, b| {
+        (acc ^ b as u64).wrapping_mul(0x0000_0100_0000_01B3)
+    })
+}
 
 /// Deterministic RNG interface.
 /// Implementations must be fully deterministic given the same seed.
@@ -22,20 +48,20 @@ pub trait IRng: Send {
     /// Restore from a saved state
     fn restore(&mut self, state: u64);
 
+    /// Split off an independent generator for sub-stream `stream`.
+    /// The result depends only on the current state and `stream`;
+    /// this generator is not advanced.
+    fn split(&self, stream: u64) -> Box<dyn IRng>;
+
     /// Generate a random u32
     fn next_u32(&mut self) -> u32;
 
     /// Generate a random u64
     fn next_u64(&mut self) -> u64;
 
-    /// Generate a random f32 in [0.0, 1.0)
-    fn next_f32(&mut self) -> f32 {
-        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
-    }
-
-    /// Generate a random f64 in [0.0, 1.0)
-    fn next_f64(&mut self) -> f64 {
-        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    /// Generate a random fixed-point number in [0, 1)
+    fn next_fixed(&mut self) -> Fixed {
+        Fixed::from_bits(self.next_u32() as i64)
     }
 
     /// Generate a random i32 in [min, max] (inclusive)
@@ -57,13 +83,33 @@ pub trait IRng: Send {
         min + (self.next_u32() % range)
     }
 
-    /// Return true with probability `chance` (0.0 to 1.0)
-    fn chance(&mut self, probability: f32) -> bool {
-        self.next_f32() < probability
+    /// Return true with probability `probability`.
+    /// Always draws exactly one u32, whatever the probability.
+    fn chance(&mut self, probability: Probability) -> bool {
+        let scaled = (self.next_u32() as u64 * probability.den() as u64) >> 32;
+        scaled < probability.num() as u64
+    }
+
+    /// Number of successes over `trials` draws of `probability`, settled by
+    /// its expectation: the whole part is exact, the fractional part is
+    /// decided by one draw (so the cost does not depend on `trials`).
+    fn expected_successes(&mut self, trials: u64, probability: Probability) -> u64 {
+        let total = trials as u128 * probability.num() as u128;
+        let den = probability.den() as u128;
+        let rest = (total % den) as u32;
+        (total / den) as u64 + self.chance(Probability::new(rest, probability.den())) as u64
+    }
+
+    /// Return true with probability `num / den` (`num` is clamped to `den`).
+    fn chance_ratio(&mut self, num: u32, den: u32) -> bool {
+        self.chance(Probability::new(num.min(den), den.max(1)))
     }
 
-    /// Pick a random element from a slice
-    fn pick<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+    /// Pick a random element from a slice (not available through `dyn IRng`)
+    fn pick<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T>
+    where
+        Self: Sized,
+    {
         if slice.is_empty() {
             None
         } else {
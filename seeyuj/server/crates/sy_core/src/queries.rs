@@ -0,0 +1,367 @@
+//! # Queries
+//!
+//! Runs `sy_api::queries::Query` against a `World`.
+//!
+//! ## Design
+//! - Works on any World: `Simulation::query` for a live one, `run_query`
+//!   directly for one loaded offline (snapshot + WAL)
+//! - Read-only: never touches the world, the RNG or the event log
+//! - Candidates come from the narrowest source (spatial index box, zone
+//!   index, then all entities), always in EntityId order; zone and world
+//!   scans seek to the cursor and are only walked up to the end of the
+//!   requested page
+
+use std::ops::Bound;
+
+use sy_api::errors::{ApiError, ApiResult};
+use sy_api::events::PropertyValue;
+use sy_api::queries::{
+    Cursor, EntityFilter, EntityView, Page, Paged, Query, QueryResult, ZoneView,
+};
+use sy_api::validation::validate_query;
+use sy_types::{EntityId, ZoneId};
+
+use crate::world::{Entity, World, Zone};
+
+/// Run a query against a world.
+pub fn run_query(world: &World, query: &Query) -> ApiResult<QueryResult> {
+    validate_query(query).map_err(ApiError::ValidationFailed)?;
+
+    let result = match query {
+        Query::Entity(id) => QueryResult::Entity(world.get_entity(*id).map(entity_view)),
+        Query::Entities { filter, page } => {
+            let found = candidates(world, filter, page.after).filter(|e| matches(filter, e));
+            QueryResult::Entities(paginate(found, page, |e| e.id.as_u64(), entity_view))
+        }
+        Query::CountEntities(filter) => QueryResult::Count(
+            candidates(world, filter, None)
+                .filter(|e| matches(filter, e))
+                .count() as u64,
+        ),
+        Query::Zone(id) => QueryResult::Zone(world.get_zone(*id).map(|z| zone_view(world, z))),
+        Query::Zones(page) => {
+            let start = match page.after {
+                Some(Cursor(last)) if last >= u32::MAX as u64 => {
+                    Bound::Excluded(ZoneId::new(u32::MAX))
+                }
+                Some(Cursor(last)) => Bound::Excluded(ZoneId::new(last as u32)),
+                None => Bound::Unbounded,
+            };
+            let zones = world.zones.range((start, Bound::Unbounded)).map(|(_, z)| z);
+            QueryResult::Zones(paginate(
+                zones,
+                page,
+                |z| z.id.as_u32() as u64,
+                |z| zone_view(world, z),
+            ))
+        }
+    };
+
+    Ok(result)
+}
+
+/// Entities that may match `filter`, after `after`, in EntityId order.
+fn candidates<'a>(
+    world: &'a World,
+    filter: &EntityFilter,
+    after: Option<Cursor>,
+) -> Box<dyn Iterator<Item = &'a Entity> + 'a> {
+    let after_id = after.map(|Cursor(last)| EntityId::new(last));
+    let ids = match (filter.zone, filter.bounds) {
+        (Some(zone), Some(bounds)) => world.entities_in_box(zone, bounds.min, bounds.max),
+        (Some(zone), None) => {
+            // The zone's spatial index holds exactly its entities, by ID
+            return Box::new(
+                world
+                    .spatial_index(zone)
+                    .into_iter()
+                    .flat_map(move |index| index.ids_after(after_id))
+                    .filter_map(|id| world.get_entity(id)),
+            );
+        }
+        (None, _) => {
+            let start = after_id.map_or(Bound::Unbounded, Bound::Excluded);
+            return Box::new(
+                world
+                    .entities
+                    .range((start, Bound::Unbounded))
+                    .map(|(_, e)| e),
+            );
+        }
+    };
+
+    Box::new(
+        ids.into_iter()
+            .filter(move |id| after_id.is_none_or(|last| *id > last))
+            .filter_map(|id| world.get_entity(id)),
+    )
+}
+
+fn matches(filter: &EntityFilter, entity: &Entity) -> bool {
+    if filter.kind.is_some_and(|kind| entity.kind != kind)
+        || filter.zone.is_some_and(|zone| entity.position.zone != zone)
+        || filter.state.is_some_and(|state| entity.state != state)
+    {
+        return false;
+    }
+    if let Some(bounds) = filter.bounds {
+        let pos = entity.position.pos;
+        let inside = (bounds.min.x..=bounds.max.x).contains(&pos.x)
+            && (bounds.min.y..=bounds.max.y).contains(&pos.y)
+            && (bounds.min.z..=bounds.max.z).contains(&pos.z);
+        if !inside {
+            return false;
+        }
+    }
+    filter
+        .properties
+        .iter()
+        .all(|range| match entity.properties.get(&range.property) {
+            Some(PropertyValue::UInt(v)) => {
+                range.min.is_none_or(|min| v >= min) && range.max.is_none_or(|max| v <= max)
+            }
+            _ => false,
+        })
+}
+
+/// Take one page of `items` (already after the cursor, in ID order).
+fn paginate<'a, T: 'a, V>(
+    items: impl Iterator<Item = &'a T>,
+    page: &Page,
+    id: impl Fn(&T) -> u64,
+    view: impl Fn(&T) -> V,
+) -> Paged<V> {
+    let limit = page.limit as usize;
+    let mut taken: Vec<&T> = items.take(limit + 1).collect();
+    let next = if taken.len() > limit {
+        taken.truncate(limit);
+        taken.last().map(|last| Cursor(id(last)))
+    } else {
+        None
+    };
+
+    Paged {
+        items: taken.into_iter().map(view).collect(),
+        next,
+    }
+}
+
+fn entity_view(entity: &Entity) -> EntityView {
+    EntityView {
+        id: entity.id,
+        kind: entity.kind,
+        state: entity.state,
+        position: entity.position,
+        created_at: entity.created_at,
+        last_changed: entity.last_changed,
+        properties: entity.properties.clone(),
+        components: entity.components.clone(),
+        expires_at: entity.expires_at,
+        attachment: entity.attachment,
+    }
+}
+
+fn zone_view(world: &World, zone: &Zone) -> ZoneView {
+    let active_count = zone
+        .entities
+        .iter()
+        .filter(|&&id| world.get_entity(id).is_some_and(Entity::is_active))
+        .count();
+
+    ZoneView {
+        id: zone.id,
+        name: zone.name.clone(),
+        loaded: zone.loaded,
+        entity_count: zone.entities.len() as u64,
+        active_count: active_count as u64,
+        neighbours: zone.neighbours.iter().copied().collect(),
+        portals: zone.portals.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sy_api::commands::EntityProperties;
+    use sy_api::queries::{Bounds, PropertyRange};
+    use sy_types::{EntityKind, EntityState, Position, Tick, WorldPos};
+
+    /// Resources 1..=10 along the x axis of the origin zone (amount = 10 * id),
+    /// creatures 11..=15 in zone 1.
+    fn world() -> World {
+        let mut world = World::new("query".to_string(), sy_types::RngSeed::new(1));
+        world.add_zone(Zone::new(ZoneId::new(1), Some("East".to_string())));
+        for i in 1..=15u64 {
+            let (kind, zone) = if i <= 10 {
+                (EntityKind::Resource, ZoneId::ORIGIN)
+            } else {
+                (EntityKind::Creature, ZoneId::new(1))
+            };
+            let id = world.allocate_entity_id();
+            assert_eq!(id, EntityId::new(i));
+            let mut entity = Entity::new(
+                id,
+                kind,
+                WorldPos::new(zone, Position::new(i as i32, 0, 0)),
+                Tick::ZERO,
+                EntityProperties {
+                    name: None,
+                    amount: (i <= 10).then_some(10 * i as u32),
+                    health: (i > 10).then_some(50),
+                },
+            );
+            if i % 5 == 0 {
+                entity.state = EntityState::Dormant;
+            }
+            world.add_entity(entity);
+        }
+        world
+    }
+
+    fn ids(result: &QueryResult) -> (Vec<u64>, Option<Cursor>) {
+        match result {
+            QueryResult::Entities(page) => (
+                page.items.iter().map(|e| e.id.as_u64()).collect(),
+                page.next,
+            ),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn entities_paginate_with_cursor() {
+        let world = world();
+        let resources = EntityFilter {
+            kind: Some(EntityKind::Resource),
+            ..Default::default()
+        };
+        let query = |page| Query::Entities {
+            filter: resources.clone(),
+            page,
+        };
+
+        let first = run_query(&world, &query(Page::first(4))).unwrap();
+        assert_eq!(ids(&first), (vec![1, 2, 3, 4], Some(Cursor(4))));
+        let second = run_query(&world, &query(Page::after(Cursor(4), 4))).unwrap();
+        assert_eq!(ids(&second), (vec![5, 6, 7, 8], Some(Cursor(8))));
+        let last = run_query(&world, &query(Page::after(Cursor(8), 4))).unwrap();
+        assert_eq!(ids(&last), (vec![9, 10], None));
+
+        assert_eq!(
+            run_query(&world, &Query::CountEntities(resources)).unwrap(),
+            QueryResult::Count(10)
+        );
+    }
+
+    #[test]
+    fn zone_filter_pages_follow_cursor() {
+        let world = world();
+        let pages = |zone| {
+            let filter = EntityFilter {
+                zone: Some(zone),
+                ..Default::default()
+            };
+            let (mut seen, mut page) = (Vec::new(), Page::first(3));
+            loop {
+                let query = Query::Entities {
+                    filter: filter.clone(),
+                    page,
+                };
+                let (items, next) = ids(&run_query(&world, &query).unwrap());
+                seen.push(items);
+                match next {
+                    Some(cursor) => page = Page::after(cursor, 3),
+                    None => return seen,
+                }
+            }
+        };
+
+        assert_eq!(
+            pages(ZoneId::ORIGIN),
+            vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9], vec![10]]
+        );
+        assert_eq!(pages(ZoneId::new(1)), vec![vec![11, 12, 13], vec![14, 15]]);
+        assert_eq!(pages(ZoneId::new(9)), vec![Vec::<u64>::new()]);
+    }
+
+    #[test]
+    fn filters_combine() {
+        let world = world();
+        let run = |filter: EntityFilter| {
+            ids(&run_query(
+                &world,
+                &Query::Entities {
+                    filter,
+                    page: Page::default(),
+                },
+            )
+            .unwrap())
+            .0
+        };
+
+        let in_box = EntityFilter {
+            zone: Some(ZoneId::ORIGIN),
+            bounds: Some(Bounds {
+                min: Position::new(3, 0, 0),
+                max: Position::new(8, 0, 0),
+            }),
+            properties: vec![PropertyRange {
+                property: "amount".to_string(),
+                min: Some(40),
+                max: None,
+            }],
+            ..Default::default()
+        };
+        assert_eq!(run(in_box.clone()), vec![4, 5, 6, 7, 8]);
+        assert_eq!(
+            run(EntityFilter {
+                state: Some(EntityState::Active),
+                ..in_box
+            }),
+            vec![4, 6, 7, 8]
+        );
+        assert_eq!(
+            run(EntityFilter {
+                zone: Some(ZoneId::new(1)),
+                state: Some(EntityState::Dormant),
+                ..Default::default()
+            }),
+            vec![15]
+        );
+    }
+
+    #[test]
+    fn zones_and_single_lookups() {
+        let world = world();
+
+        let QueryResult::Zones(page) = run_query(&world, &Query::Zones(Page::first(1))).unwrap()
+        else {
+            panic!("expected zones");
+        };
+        assert_eq!(page.items[0].id, ZoneId::ORIGIN);
+        assert_eq!(page.next, Some(Cursor(0)));
+
+        let QueryResult::Zones(page) =
+            run_query(&world, &Query::Zones(Page::after(Cursor(0), 1))).unwrap()
+        else {
+            panic!("expected zones");
+        };
+        assert_eq!(page.items[0].name.as_deref(), Some("East"));
+        assert_eq!(
+            (page.items[0].entity_count, page.items[0].active_count),
+            (5, 4)
+        );
+        assert_eq!(page.next, None);
+
+        let QueryResult::Entity(Some(entity)) =
+            run_query(&world, &Query::Entity(EntityId::new(3))).unwrap()
+        else {
+            panic!("expected an entity");
+        };
+        assert_eq!(entity.properties.amount, Some(30));
+        assert_eq!(
+            run_query(&world, &Query::Zone(ZoneId::new(9))).unwrap(),
+            QueryResult::Zone(None)
+        );
+    }
+}
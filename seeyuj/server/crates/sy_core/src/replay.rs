@@ -10,10 +10,16 @@
 //! - `apply_event` is deterministic and total
 //! - No I/O, no RNG, no system time
 //! - Idempotent when event_id is checked by caller
+//!
+//! ## Time travel
+//! `state_at` rebuilds the world as it was at any past tick, from the
+//! snapshot when it is old enough, or else from genesis (`WorldCreated`).
 
 use sy_api::events::{EventData, SimEvent};
-use sy_types::{EntityState, SimTime};
+use sy_types::{EntityState, EventId, SimTime, Tick};
 
+use crate::inputs::QueuedInput;
+use crate::timers::Timer;
 use crate::world::{Entity, World, Zone};
 
 /// Apply a single event to the world state.
@@ -28,6 +34,15 @@ use crate::world::{Entity, World, Zone};
 /// - `Err(reason)` if event could not be applied (e.g., entity not found)
 ///   In crash recovery, errors are typically logged but not fatal.
 pub fn apply_event(world: &mut World, event: &SimEvent) -> Result<(), String> {
+    let result = apply_event_data(world, event);
+
+    // Keep dormancy bookkeeping in sync with the live simulation
+    world.touch_entity(&event.data, event.tick);
+
+    result
+}
+
+fn apply_event_data(world: &mut World, event: &SimEvent) -> Result<(), String> {
     // Update world tick to max of current and event tick
     if event.tick > world.current_tick {
         world.current_tick = event.tick;
@@ -52,6 +67,10 @@ pub fn apply_event(world: &mut World, event: &SimEvent) -> Result<(), String> {
             // No state change needed
             Ok(())
         }
+        EventData::CatchUpDecided { .. } => {
+            // Decision record: the catch-up ticks have their own events
+            Ok(())
+        }
 
         // ====================================================================
         // Tick events
@@ -86,9 +105,44 @@ pub fn apply_event(world: &mut World, event: &SimEvent) -> Result<(), String> {
         EventData::ZoneUnloaded { zone_id } => {
             if let Some(zone) = world.zones.get_mut(zone_id) {
                 zone.loaded = false;
+                zone.simulated_until = event.tick;
             }
             Ok(())
         }
+        EventData::ZoneCoarseStepped { zone_id, to, .. } => {
+            if let Some(zone) = world.zones.get_mut(zone_id) {
+                zone.simulated_until = *to;
+            }
+            Ok(())
+        }
+        EventData::ZonesLinked { a, b } => {
+            world.link_zones(*a, *b);
+            Ok(())
+        }
+        EventData::ZonesUnlinked { a, b } => {
+            world.unlink_zones(*a, *b);
+            Ok(())
+        }
+        EventData::PortalAdded { zone_id, portal } => {
+            world.add_portal(*zone_id, *portal);
+            Ok(())
+        }
+        EventData::PortalRemoved { zone_id, from } => {
+            if let Some(zone) = world.zones.get_mut(zone_id) {
+                zone.remove_portal(*from);
+            }
+            Ok(())
+        }
+
+        // ====================================================================
+        // Archetype events
+        // ====================================================================
+        EventData::ArchetypeDefined { archetype } => {
+            world
+                .archetypes
+                .insert(archetype.name.clone(), archetype.clone());
+            Ok(())
+        }
 
         // ====================================================================
         // Entity events
@@ -98,6 +152,8 @@ pub fn apply_event(world: &mut World, event: &SimEvent) -> Result<(), String> {
             kind,
             position,
             properties,
+            components,
+            expires_at,
         } => {
             // Don't re-spawn if entity already exists
             if world.entities.contains_key(entity_id) {
@@ -109,7 +165,10 @@ pub fn apply_event(world: &mut World, event: &SimEvent) -> Result<(), String> {
                 world.next_entity_id = entity_id.as_u64() + 1;
             }
 
-            let entity = Entity::new(*entity_id, *kind, *position, event.tick, properties.clone());
+            let mut entity =
+                Entity::new(*entity_id, *kind, *position, event.tick, properties.clone())
+                    .with_components(components.clone());
+            entity.expires_at = *expires_at;
             world.add_entity(entity);
             Ok(())
         }
@@ -119,23 +178,9 @@ pub fn apply_event(world: &mut World, event: &SimEvent) -> Result<(), String> {
             Ok(())
         }
 
-        EventData::EntityMoved {
-            entity_id,
-            from,
-            to,
-        } => {
-            if let Some(entity) = world.entities.get_mut(entity_id) {
-                // Update zone membership
-                if from.zone != to.zone {
-                    if let Some(old_zone) = world.zones.get_mut(&from.zone) {
-                        old_zone.remove_entity(*entity_id);
-                    }
-                    if let Some(new_zone) = world.zones.get_mut(&to.zone) {
-                        new_zone.add_entity(*entity_id);
-                    }
-                }
-                entity.position = *to;
-            }
+        EventData::EntityMoved { entity_id, to, .. } => {
+            // Zone membership follows the entity's actual position
+            world.move_entity(*entity_id, *to);
             Ok(())
         }
 
@@ -144,7 +189,9 @@ pub fn apply_event(world: &mut World, event: &SimEvent) -> Result<(), String> {
             new_state,
             ..
         } => {
-            if let Some(entity) = world.entities.get_mut(entity_id) {
+            if *new_state == EntityState::Dead {
+                world.mark_dead(*entity_id);
+            } else if let Some(entity) = world.entities.get_mut(entity_id) {
                 entity.state = *new_state;
             }
             Ok(())
@@ -155,28 +202,77 @@ pub fn apply_event(world: &mut World, event: &SimEvent) -> Result<(), String> {
             property,
             new_value,
             ..
+        } => match world.entities.get_mut(entity_id) {
+            Some(entity) => entity.properties.set(property, new_value),
+            None => Ok(()),
+        },
+
+        EventData::EntityExpiryChanged {
+            entity_id,
+            new_expires_at,
+            ..
         } => {
-            if let Some(entity) = world.entities.get_mut(entity_id) {
-                // Apply property change based on property name
-                match property.as_str() {
-                    "name" => {
-                        if let sy_api::events::PropertyValue::String(s) = new_value {
-                            entity.properties.name = Some(s.clone());
-                        }
-                    }
-                    "amount" => {
-                        if let sy_api::events::PropertyValue::UInt(v) = new_value {
-                            entity.properties.amount = Some(*v as u32);
-                        }
-                    }
-                    "health" => {
-                        if let sy_api::events::PropertyValue::UInt(v) = new_value {
-                            entity.properties.health = Some(*v as u32);
-                        }
-                    }
-                    _ => {} // Unknown property, ignore
-                }
-            }
+            world.set_entity_expiry(*entity_id, *new_expires_at);
+            Ok(())
+        }
+        EventData::EntityAttached {
+            entity_id,
+            attachment,
+            ..
+        } => world.attach_entity(*entity_id, *attachment).map(|_| ()),
+        EventData::EntityDetached { entity_id, .. } => {
+            world.detach_entity(*entity_id);
+            Ok(())
+        }
+
+        // ====================================================================
+        // Timer events
+        // ====================================================================
+        EventData::TimerScheduled {
+            timer_id,
+            fire_at,
+            repeat_every,
+            command,
+        } => {
+            world.timers.insert(Timer {
+                id: *timer_id,
+                fire_at: *fire_at,
+                repeat_every: *repeat_every,
+                command: command.clone(),
+            });
+            Ok(())
+        }
+        EventData::TimerFired {
+            timer_id,
+            next_fire,
+        } => {
+            world.timers.complete(*timer_id, *next_fire);
+            Ok(())
+        }
+        EventData::TimerCancelled { timer_id } => {
+            world.timers.remove(*timer_id);
+            Ok(())
+        }
+
+        // ====================================================================
+        // Input events
+        // ====================================================================
+        EventData::InputQueued {
+            source,
+            seq,
+            at,
+            command,
+        } => {
+            world.inputs.insert(QueuedInput {
+                source: *source,
+                seq: *seq,
+                at: *at,
+                command: command.clone(),
+            });
+            Ok(())
+        }
+        EventData::InputApplied { source, seq, at } => {
+            world.inputs.remove(*at, *source, *seq);
             Ok(())
         }
 
@@ -191,7 +287,7 @@ pub fn apply_event(world: &mut World, event: &SimEvent) -> Result<(), String> {
             if let Some(entity) = world.entities.get_mut(entity_id) {
                 entity.properties.amount = Some(*remaining);
                 if *remaining == 0 {
-                    entity.state = EntityState::Dead;
+                    world.mark_dead(*entity_id);
                 }
             }
             Ok(())
@@ -205,7 +301,7 @@ pub fn apply_event(world: &mut World, event: &SimEvent) -> Result<(), String> {
             if let Some(entity) = world.entities.get_mut(entity_id) {
                 entity.properties.health = Some(*new_health);
                 if *new_health == 0 {
-                    entity.state = EntityState::Dead;
+                    world.mark_dead(*entity_id);
                 }
             }
             Ok(())
@@ -225,11 +321,84 @@ pub fn replay_events(world: &mut World, events: &[SimEvent]) -> usize {
     applied
 }
 
+/// Rebuild the world as it was at the end of `tick` (after every event
+/// recorded at that tick), for inspection.
+///
+/// Starts from `snapshot` if it was taken at or before `tick`, otherwise
+/// from genesis: a fresh world built from the `WorldCreated` event. Then
+/// applies `events` (the whole log, in event_id order) up to `tick`. The
+/// result is detached from any simulation and never persisted.
+pub fn state_at(snapshot: Option<World>, events: &[SimEvent], tick: Tick) -> Result<World, String> {
+    let reached = events
+        .last()
+        .map(|e| e.tick)
+        .into_iter()
+        .chain(snapshot.as_ref().map(|w| w.current_tick))
+        .max()
+        .unwrap_or(Tick::ZERO);
+    if tick > reached {
+        return Err(format!("The world only reached {}", reached));
+    }
+
+    let last = events
+        .iter()
+        .take_while(|e| e.tick <= tick)
+        .last()
+        .map_or(EventId::ZERO, |e| e.event_id);
+    rebuild(snapshot, events, last)
+}
+
+/// Rebuild the world right after event `last`: from `snapshot` if it does
+/// not include later events, otherwise from genesis.
+pub(crate) fn rebuild(
+    snapshot: Option<World>,
+    events: &[SimEvent],
+    last: EventId,
+) -> Result<World, String> {
+    let mut world = match snapshot.filter(|w| w.meta.last_event_id <= last) {
+        Some(world) => world,
+        None => genesis(events)?,
+    };
+
+    let from = world.meta.last_event_id;
+    for event in events
+        .iter()
+        .filter(|e| e.event_id > from)
+        .take_while(|e| e.event_id <= last)
+    {
+        // Same tolerance as crash recovery (see `replay_events`)
+        let _ = apply_event(&mut world, event);
+        world.meta.last_event_id = event.event_id;
+    }
+
+    Ok(world)
+}
+
+/// The world as created, before its first event.
+fn genesis(events: &[SimEvent]) -> Result<World, String> {
+    events
+        .iter()
+        .find_map(|event| match &event.data {
+            EventData::WorldCreated {
+                world_id,
+                name,
+                seed,
+            } => {
+                let mut world = World::new(name.clone(), *seed);
+                world.meta.world_id = world_id.clone();
+                Some(world)
+            }
+            _ => None,
+        })
+        .ok_or_else(|| "No WorldCreated event in the log: cannot start from genesis".to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use sy_api::commands::EntityProperties;
-    use sy_types::{EntityId, EntityKind, EventId, RngSeed, Tick, WorldPos};
+    use sy_api::components::Components;
+    use sy_types::{EntityId, EntityKind, EventId, Position, RngSeed, Tick, WorldPos, ZoneId};
 
     #[test]
     fn replay_entity_spawn() {
@@ -243,6 +412,8 @@ mod tests {
                 kind: EntityKind::Resource,
                 position: WorldPos::origin(),
                 properties: EntityProperties::default(),
+                components: Components::default(),
+                expires_at: None,
             },
         );
 
@@ -251,6 +422,71 @@ mod tests {
         assert!(world.get_entity(EntityId::new(1)).is_some());
     }
 
+    #[test]
+    fn replay_entity_spawn_restores_components() {
+        let mut world = World::new("Test".to_string(), RngSeed::new(1));
+
+        let mut components = Components::new();
+        components
+            .insert_raw("velocity", serde_json::json!({ "dx": 1 }))
+            .unwrap();
+
+        let event = SimEvent::with_id(
+            EventId::new(1),
+            Tick(1),
+            EventData::EntitySpawned {
+                entity_id: EntityId::new(1),
+                kind: EntityKind::Creature,
+                position: WorldPos::origin(),
+                properties: EntityProperties::default(),
+                components: components.clone(),
+                expires_at: None,
+            },
+        );
+
+        apply_event(&mut world, &event).unwrap();
+        let entity = world.get_entity(EntityId::new(1)).unwrap();
+        assert_eq!(entity.components, components);
+    }
+
+    #[test]
+    fn replay_entity_moved_across_zones() {
+        let mut world = World::new("Test".to_string(), RngSeed::new(1));
+        world.add_zone(Zone::new(ZoneId::new(1), None));
+
+        let spawn = SimEvent::with_id(
+            EventId::new(1),
+            Tick(1),
+            EventData::EntitySpawned {
+                entity_id: EntityId::new(1),
+                kind: EntityKind::Creature,
+                position: WorldPos::origin(),
+                properties: EntityProperties::default(),
+                components: Components::default(),
+                expires_at: None,
+            },
+        );
+        let to = WorldPos::new(ZoneId::new(1), Position::new(5, 0, 0));
+        let moved = SimEvent::with_id(
+            EventId::new(2),
+            Tick(2),
+            EventData::EntityMoved {
+                entity_id: EntityId::new(1),
+                from: WorldPos::origin(),
+                to,
+            },
+        );
+
+        replay_events(&mut world, &[spawn, moved]);
+
+        assert!(world.get_zone(ZoneId::ORIGIN).unwrap().entities.is_empty());
+        assert_eq!(
+            world.get_zone(ZoneId::new(1)).unwrap().entities,
+            vec![EntityId::new(1)]
+        );
+        assert_eq!(world.get_entity(EntityId::new(1)).unwrap().position, to);
+    }
+
     #[test]
     fn replay_is_idempotent() {
         let mut world = World::new("Test".to_string(), RngSeed::new(1));
@@ -263,6 +499,8 @@ mod tests {
                 kind: EntityKind::Resource,
                 position: WorldPos::origin(),
                 properties: EntityProperties::default(),
+                components: Components::default(),
+                expires_at: None,
             },
         );
 
@@ -292,4 +530,59 @@ mod tests {
         apply_event(&mut world, &event).unwrap();
         assert_eq!(world.current_tick, Tick(100));
     }
+
+    #[test]
+    fn state_at_starts_from_snapshot_or_genesis() {
+        let at = |x| WorldPos::new(ZoneId::ORIGIN, Position::new(x, 0, 0));
+        let events = vec![
+            SimEvent::with_id(
+                EventId::new(1),
+                Tick(0),
+                EventData::WorldCreated {
+                    world_id: "world_7".to_string(),
+                    name: "Past".to_string(),
+                    seed: RngSeed::new(7),
+                },
+            ),
+            SimEvent::with_id(
+                EventId::new(2),
+                Tick(1),
+                EventData::EntitySpawned {
+                    entity_id: EntityId::new(1),
+                    kind: EntityKind::Creature,
+                    position: at(0),
+                    properties: EntityProperties::default(),
+                    components: Components::default(),
+                    expires_at: None,
+                },
+            ),
+            SimEvent::with_id(
+                EventId::new(3),
+                Tick(3),
+                EventData::EntityMoved {
+                    entity_id: EntityId::new(1),
+                    from: at(0),
+                    to: at(5),
+                },
+            ),
+        ];
+        let position = |world: &World| world.get_entity(EntityId::new(1)).map(|e| e.position);
+
+        let past = state_at(None, &events, Tick(2)).unwrap();
+        assert_eq!(past.id(), "world_7");
+        assert_eq!(past.current_tick, Tick(1));
+        assert_eq!(position(&past), Some(at(0)));
+        assert_eq!(position(&state_at(None, &events, Tick(0)).unwrap()), None);
+        assert!(state_at(None, &events, Tick(4)).is_err());
+
+        // A snapshot past the requested tick is not used
+        let mut snapshot = state_at(None, &events, Tick(3)).unwrap();
+        snapshot.meta.snapshot_tick = Tick(3);
+        assert_eq!(position(&snapshot), Some(at(5)));
+        let past = state_at(Some(snapshot.clone()), &events, Tick(2)).unwrap();
+        assert_eq!(position(&past), Some(at(0)));
+        let now = state_at(Some(snapshot), &events, Tick(3)).unwrap();
+        assert_eq!(now.meta.last_event_id, EventId::new(3));
+        assert!(state_at(None, &events[1..], Tick(2)).is_err());
+    }
 }
@@ -12,17 +12,58 @@
 //! 1. Load snapshot (state at snapshot_tick)
 //! 2. Read events with event_id > last_event_id
 //! 3. Replay events using apply_event()
-
-use sy_api::commands::{Command, CreateWorldCmd, CreateZoneCmd, SpawnEntityCmd};
+//!
+//! ## Strict mode
+//! With `set_strict(true)`, the world invariants (see `invariants`) are
+//! checked after every tick and after crash recovery. A violation fails the
+//! command with `ApiError::InvariantViolated` before anything reaches the
+//! event log, and the world is restored as it was before the command (all
+//! of its ticks, for `TickN`), so the faulty tick is never persisted.
+//!
+//! ## Subscriptions
+//! Subscribers (`subscribe`) receive the events of every command once they
+//! are in the event log, in `event_id` order, filtered by type, zone or
+//! entity (see `subscriptions`). The zone of an entity event is the one
+//! the entity was in when the event was emitted.
+//!
+//! ## Level of detail
+//! Unloaded zones are not ticked. By default they are frozen; with
+//! `set_coarse_interval(n)` every unloaded zone is advanced every `n` ticks
+//! by the coarse models of the systems (`ITickSystem::run_coarse`), over
+//! the ticks elapsed since it was last simulated, and once more when it is
+//! loaded again. Each step is recorded as `ZoneCoarseStepped` followed by
+//! its entity events, so replay reproduces it exactly.
+
+use sy_api::archetypes::Archetype;
+use sy_api::commands::{
+    AddPortalCmd, AttachEntityCmd, CatchUpCmd, Command, CreateWorldCmd, CreateZoneCmd,
+    LinkZonesCmd, MoveEntityCmd, QueueInputCmd, RemovePortalCmd, ScheduleCmd, SetEntityExpiryCmd,
+    SetEntityPropertyCmd, SpawnEntityCmd, SpawnFromArchetypeCmd,
+};
 use sy_api::errors::{ApiError, ApiResult};
 use sy_api::events::{DespawnReason, EventData, SimEvent};
-use sy_types::{EntityId, EntityKind, EntityState, Tick, ZoneId};
+use sy_api::queries::{Query, QueryResult};
+use sy_types::{Attachment, EntityId, EntityState, Tick, TimerId, ZoneId};
 use tracing::{debug, info, warn};
 
+use crate::inputs::QueuedInput;
+use crate::invariants::check_invariants;
 use crate::ports::{IEventLog, IRng, ISimClock, IWorldStore};
+use crate::queries::run_query;
 use crate::replay::apply_event;
+use crate::subscriptions::{EventBus, EventFilter, IEventSubscriber, SubscriptionId};
+use crate::systems::{self, ITickSystem, SystemId, ZoneShard};
+use crate::timers::Timer;
 use crate::world::{Entity, World, Zone};
 
+/// State restored when a command must leave no trace (see `rollback`).
+struct Checkpoint {
+    world: Option<World>,
+    rng_state: u64,
+    tick: Tick,
+    pending: usize,
+}
+
 /// The simulation engine.
 /// Processes commands, runs tick logic, emits events.
 pub struct Simulation<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> {
@@ -38,11 +79,27 @@ pub struct Simulation<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> {
     store: S,
     /// Events pending to be recorded
     pending_events: Vec<SimEvent>,
+    /// Zone of the entity of each pending event when it was emitted
+    pending_zones: Vec<Option<ZoneId>>,
+    /// Subscribers to recorded events
+    bus: EventBus,
+    /// Registered tick systems, kept in execution order
+    systems: Vec<Box<dyn ITickSystem>>,
+    /// Number of threads used to tick zones
+    tick_threads: usize,
+    /// Check world invariants after every tick and after recovery
+    strict: bool,
+    /// Ticks between coarse steps of unloaded zones (0: frozen)
+    coarse_interval: u64,
 }
 
 impl<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> Simulation<R, C, E, S> {
     /// Create a new simulation with injected dependencies.
+    /// The built-in tick systems are registered by default.
     pub fn new(rng: R, clock: C, event_log: E, store: S) -> Self {
+        let mut systems = systems::default_systems();
+        systems::sort_systems(&mut systems);
+
         Simulation {
             world: None,
             rng,
@@ -50,6 +107,12 @@ impl<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> Simulation<R, C, E, S>
             event_log,
             store,
             pending_events: Vec::new(),
+            pending_zones: Vec::new(),
+            bus: EventBus::new(),
+            systems,
+            tick_threads: 1,
+            strict: false,
+            coarse_interval: 0,
         }
     }
 
@@ -76,9 +139,103 @@ impl<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> Simulation<R, C, E, S>
             .unwrap_or(Tick::ZERO)
     }
 
-    /// Get access to the RNG.
-    pub fn rng(&mut self) -> &mut R {
-        &mut self.rng
+    /// Get read access to the event log.
+    pub fn event_log(&self) -> &E {
+        &self.event_log
+    }
+
+    /// Flush the event log to durable storage.
+    /// Everything returned by `process_command` so far survives a crash.
+    pub fn sync_event_log(&mut self) -> ApiResult<()> {
+        self.event_log
+            .sync()
+            .map_err(|e| ApiError::StorageError(e.to_string()))
+    }
+
+    /// Get read access to the world store.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    // ========================================================================
+    // System registration
+    // ========================================================================
+
+    /// Register a tick system.
+    /// A system with the same ID is replaced (e.g. to override a built-in rule).
+    pub fn register_system(&mut self, system: Box<dyn ITickSystem>) {
+        self.systems.retain(|s| s.id() != system.id());
+        self.systems.push(system);
+        systems::sort_systems(&mut self.systems);
+    }
+
+    /// Remove a tick system by ID. Returns true if it was registered.
+    pub fn unregister_system(&mut self, id: &str) -> bool {
+        let before = self.systems.len();
+        self.systems.retain(|s| s.id() != id);
+        self.systems.len() != before
+    }
+
+    /// Set the number of threads used to tick zones (minimum 1).
+    /// Results are identical for any thread count.
+    pub fn set_tick_threads(&mut self, threads: usize) {
+        self.tick_threads = threads.max(1);
+    }
+
+    /// Number of threads used to tick zones.
+    pub fn tick_threads(&self) -> usize {
+        self.tick_threads
+    }
+
+    /// Enable or disable strict mode (invariant checks after every tick and
+    /// after crash recovery). Off by default: each check walks the whole
+    /// world, and each `Tick` or `TickN` command first copies the whole world
+    /// to restore it if a check fails, so every tick costs O(world).
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Whether strict mode is on.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Set the number of ticks between coarse steps of unloaded zones
+    /// (0 freezes them, the default). Steps are recorded in the event log,
+    /// so replay does not depend on it, but a live run does: keep it fixed
+    /// for a given world so that two runs take the same steps.
+    pub fn set_coarse_interval(&mut self, ticks: u64) {
+        self.coarse_interval = ticks;
+    }
+
+    /// Ticks between coarse steps of unloaded zones (0: frozen).
+    pub fn coarse_interval(&self) -> u64 {
+        self.coarse_interval
+    }
+
+    /// Run a read-only query against the current world.
+    pub fn query(&self, query: &Query) -> ApiResult<QueryResult> {
+        run_query(self.world.as_ref().ok_or(ApiError::NoWorldLoaded)?, query)
+    }
+
+    /// Subscribe to recorded events matching `filter`, from the next
+    /// command on.
+    pub fn subscribe(
+        &mut self,
+        filter: EventFilter,
+        subscriber: Box<dyn IEventSubscriber>,
+    ) -> SubscriptionId {
+        self.bus.subscribe(filter, subscriber)
+    }
+
+    /// Remove a subscriber. Returns false if it was not subscribed.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        self.bus.unsubscribe(id)
+    }
+
+    /// IDs of registered systems, in execution order.
+    pub fn system_ids(&self) -> Vec<SystemId> {
+        self.systems.iter().map(|s| s.id()).collect()
     }
 
     // ========================================================================
@@ -93,7 +250,38 @@ impl<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> Simulation<R, C, E, S>
         }
 
         self.pending_events.clear();
+        self.pending_zones.clear();
+
+        // A rejected tick must not stay applied in memory either. Only ticks
+        // run the checker (a batch of ticks restores itself)
+        let ticks = matches!(cmd, Command::Tick | Command::TickN(_));
+        let checkpoint = (self.strict && ticks).then(|| self.checkpoint());
+        if let Err(error) = self.execute(cmd) {
+            if let Some(checkpoint) = checkpoint {
+                self.rollback(checkpoint);
+            }
+            return Err(error);
+        }
+
+        // Record events to log (assigns event_id to each event)
+        let persisted = if !self.pending_events.is_empty() {
+            let events = std::mem::take(&mut self.pending_events);
+            self.event_log
+                .append_batch(events)
+                .map_err(|e| ApiError::StorageError(e.to_string()))?
+        } else {
+            Vec::new()
+        };
+
+        // Only what reached the event log is delivered
+        let zones = std::mem::take(&mut self.pending_zones);
+        self.bus.publish(&persisted, &zones);
+
+        Ok(persisted)
+    }
 
+    /// Dispatch a validated command. Events are left in `pending_events`.
+    fn execute(&mut self, cmd: Command) -> ApiResult<()> {
         match cmd {
             Command::CreateWorld(c) => self.cmd_create_world(c)?,
             Command::LoadWorld(c) => self.cmd_load_world(&c.world_id)?,
@@ -104,9 +292,29 @@ impl<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> Simulation<R, C, E, S>
                     self.cmd_tick()?;
                 }
             }
+            Command::CatchUp(c) => self.cmd_catch_up(c)?,
             Command::SpawnEntity(c) => self.cmd_spawn_entity(c)?,
+            Command::SpawnFromArchetype(c) => self.cmd_spawn_from_archetype(c)?,
+            Command::DefineArchetype(a) => self.cmd_define_archetype(a)?,
             Command::DespawnEntity(id) => self.cmd_despawn_entity(id)?,
+            Command::MoveEntity(c) => self.cmd_move_entity(c)?,
+            Command::SleepEntity(id) => self.cmd_set_entity_state(id, EntityState::Dormant)?,
+            Command::WakeEntity(id) => self.cmd_set_entity_state(id, EntityState::Active)?,
+            Command::SetEntityProperty(c) => self.cmd_set_entity_property(c)?,
+            Command::SetEntityExpiry(c) => self.cmd_set_entity_expiry(c)?,
+            Command::AttachEntity(c) => self.cmd_attach_entity(c)?,
+            Command::DetachEntity(id) => self.cmd_detach_entity(id)?,
             Command::CreateZone(c) => self.cmd_create_zone(c)?,
+            Command::LoadZone(id) => self.cmd_set_zone_loaded(id, true)?,
+            Command::UnloadZone(id) => self.cmd_set_zone_loaded(id, false)?,
+            Command::LinkZones(c) => self.cmd_link_zones(c)?,
+            Command::UnlinkZones(c) => self.cmd_unlink_zones(c)?,
+            Command::AddPortal(c) => self.cmd_add_portal(c)?,
+            Command::RemovePortal(c) => self.cmd_remove_portal(c)?,
+            Command::ScheduleCommand(c) => self.cmd_schedule(c)?,
+            Command::CancelTimer(id) => self.cmd_cancel_timer(id)?,
+            Command::QueueInput(c) => self.cmd_queue_input(c)?,
+            Command::Batch(commands) => self.cmd_batch(commands)?,
             Command::Shutdown => {
                 // Save before shutdown
                 if self.world.is_some() {
@@ -115,22 +323,16 @@ impl<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> Simulation<R, C, E, S>
             }
         }
 
-        // Record events to log (assigns event_id to each event)
-        let persisted = if !self.pending_events.is_empty() {
-            let events = std::mem::take(&mut self.pending_events);
-            self.event_log
-                .append_batch(events)
-                .map_err(|e| ApiError::StorageError(e.to_string()))?
-        } else {
-            Vec::new()
-        };
-
-        Ok(persisted)
+        Ok(())
     }
 
     // ========================================================================
     // Command implementations
     // ========================================================================
+    //
+    // Every handler checks its preconditions before touching the world and
+    // emits last, so a failing command changes nothing. Only `cmd_batch`
+    // needs a checkpoint: its steps may fail after earlier ones applied.
 
     fn cmd_create_world(&mut self, cmd: CreateWorldCmd) -> ApiResult<()> {
         let world = World::new(cmd.name.clone(), cmd.seed);
@@ -233,6 +435,10 @@ impl<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> Simulation<R, C, E, S>
             );
         }
 
+        if self.strict {
+            Self::check_world(&world)?;
+        }
+
         // Restore RNG state
         self.rng.restore(world.rng_state);
 
@@ -254,8 +460,9 @@ impl<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> Simulation<R, C, E, S>
     fn cmd_save_world(&mut self) -> ApiResult<()> {
         let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
 
-        // Update RNG state in world
-        world.rng_state = self.rng.state();
+        // `world.rng_state` is the fixed root of every stream: it is never
+        // taken back from the live generator, so a rebuild from genesis
+        // (replay, state_at, fork) derives the same streams
 
         // Update snapshot metadata for crash recovery
         world.meta.snapshot_tick = world.current_tick;
@@ -301,15 +508,102 @@ impl<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> Simulation<R, C, E, S>
         let tick = world.current_tick;
         let sim_time = world.sim_time;
 
-        // Run systemic rules
+        // Apply external inputs, fire scheduled commands, then run systemic rules
+        self.apply_inputs(tick)?;
+        self.fire_timers(tick)?;
         let entities_processed = self.run_tick_systems()?;
 
+        if self.coarse_interval > 0 && tick.as_u64() % self.coarse_interval == 0 {
+            let world = self.world.as_ref().ok_or(ApiError::NoWorldLoaded)?;
+            let behind: Vec<ZoneId> = world
+                .zones
+                .values()
+                .filter(|z| !z.loaded && z.simulated_until < tick)
+                .map(|z| z.id)
+                .collect();
+            self.run_coarse_steps(&behind)?;
+        }
+
         self.emit(EventData::TickProcessed {
             tick,
             sim_time,
             entities_processed,
         });
 
+        if self.strict {
+            Self::check_world(self.world.as_ref().ok_or(ApiError::NoWorldLoaded)?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Record a catch-up decision. The ticks themselves are run by the
+    /// caller, as ordinary ticks.
+    fn cmd_catch_up(&mut self, cmd: CatchUpCmd) -> ApiResult<()> {
+        let world = self.world.as_ref().ok_or(ApiError::NoWorldLoaded)?;
+        info!(
+            "Catching up {} of {} missed ticks from {}",
+            cmd.ticks, cmd.missed_ticks, world.current_tick
+        );
+
+        self.emit(EventData::CatchUpDecided {
+            offline_ms: cmd.offline_ms,
+            missed_ticks: cmd.missed_ticks,
+            ticks: cmd.ticks,
+            skipped_ticks: cmd.missed_ticks - cmd.ticks,
+        });
+
+        Ok(())
+    }
+
+    /// Fail with the full report if `world` breaks an invariant.
+    fn check_world(world: &World) -> ApiResult<()> {
+        let report = check_invariants(world);
+        if report.is_ok() {
+            return Ok(());
+        }
+        warn!("{}", report);
+        Err(ApiError::InvariantViolated {
+            tick: report.tick,
+            violations: report.lines(),
+        })
+    }
+
+    /// Capture world, RNG, clock and pending events.
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            world: self.world.clone(),
+            rng_state: self.rng.state(),
+            tick: self.clock.current_tick(),
+            pending: self.pending_events.len(),
+        }
+    }
+
+    /// Go back to `checkpoint`: the events emitted since are dropped, so
+    /// nothing of what happened in between reaches the event log.
+    fn rollback(&mut self, checkpoint: Checkpoint) {
+        self.world = checkpoint.world;
+        self.rng.restore(checkpoint.rng_state);
+        self.clock.set_tick(checkpoint.tick);
+        self.pending_events.truncate(checkpoint.pending);
+        self.pending_zones.truncate(checkpoint.pending);
+    }
+
+    /// Run commands as one unit. On failure, world, RNG, clock and pending
+    /// events are restored, so nothing reaches the event log.
+    fn cmd_batch(&mut self, commands: Vec<Command>) -> ApiResult<()> {
+        let checkpoint = self.checkpoint();
+
+        for (index, command) in commands.into_iter().enumerate() {
+            if let Err(error) = self.execute(command) {
+                self.rollback(checkpoint);
+                return Err(ApiError::BatchFailed {
+                    index,
+                    error: Box::new(error),
+                });
+            }
+        }
+
         Ok(())
     }
 
@@ -321,10 +615,19 @@ impl<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> Simulation<R, C, E, S>
             return Err(ApiError::ZoneNotFound(cmd.position.zone));
         }
 
-        let id = world.allocate_entity_id();
         let tick = world.current_tick;
+        if cmd.expires_at.is_some_and(|at| at <= tick) {
+            return Err(ApiError::InvalidCommand(format!(
+                "Expiry tick must be after current tick {}",
+                tick
+            )));
+        }
 
-        let entity = Entity::new(id, cmd.kind, cmd.position, tick, cmd.properties.clone());
+        let id = world.allocate_entity_id();
+
+        let mut entity = Entity::new(id, cmd.kind, cmd.position, tick, cmd.properties.clone())
+            .with_components(cmd.components.clone());
+        entity.expires_at = cmd.expires_at;
 
         world.add_entity(entity);
 
@@ -333,21 +636,228 @@ impl<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> Simulation<R, C, E, S>
             kind: cmd.kind,
             position: cmd.position,
             properties: cmd.properties,
+            components: cmd.components,
+            expires_at: cmd.expires_at,
         });
 
         Ok(())
     }
 
+    /// Spawn from a template of the world registry. The event records the
+    /// resolved entity, so replay never looks the archetype up.
+    fn cmd_spawn_from_archetype(&mut self, cmd: SpawnFromArchetypeCmd) -> ApiResult<()> {
+        let world = self.world.as_ref().ok_or(ApiError::NoWorldLoaded)?;
+        let archetype = world
+            .archetypes
+            .get(&cmd.archetype)
+            .ok_or_else(|| ApiError::ArchetypeNotFound(cmd.archetype.clone()))?;
+
+        let spawn = SpawnEntityCmd {
+            position: cmd.position,
+            kind: archetype.kind,
+            properties: archetype.resolve_properties(&cmd.properties),
+            components: archetype
+                .resolve_components()
+                .map_err(ApiError::InvalidCommand)?,
+            expires_at: cmd.expires_at,
+        };
+
+        self.cmd_spawn_entity(spawn)
+    }
+
+    fn cmd_define_archetype(&mut self, archetype: Archetype) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+
+        world
+            .archetypes
+            .insert(archetype.name.clone(), archetype.clone());
+        self.emit(EventData::ArchetypeDefined { archetype });
+
+        Ok(())
+    }
+
     fn cmd_despawn_entity(&mut self, id: EntityId) -> ApiResult<()> {
         let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+        let zone = world.get_entity(id).map(|e| e.position.zone);
+
+        let events = world
+            .despawn_entity(id, DespawnReason::Command)
+            .ok_or(ApiError::EntityNotFound(id))?;
+
+        // Attached entities despawned with it were in the same zone
+        for event in events {
+            self.emit_in_zone(event, zone);
+        }
+
+        Ok(())
+    }
+
+    fn cmd_move_entity(&mut self, cmd: MoveEntityCmd) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+
+        if !world.has_zone(cmd.to.zone) {
+            return Err(ApiError::ZoneNotFound(cmd.to.zone));
+        }
+        if let Some(parent) = world.get_entity(cmd.entity_id).and_then(Entity::parent) {
+            return Err(ApiError::InvalidCommand(format!(
+                "Entity {} is attached to {}: move the parent or detach it first",
+                cmd.entity_id, parent
+            )));
+        }
+
+        let from = world
+            .move_entity(cmd.entity_id, cmd.to)
+            .ok_or(ApiError::EntityNotFound(cmd.entity_id))?;
+
+        if from != cmd.to {
+            self.emit(EventData::EntityMoved {
+                entity_id: cmd.entity_id,
+                from,
+                to: cmd.to,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Toggle an entity between Active and Dormant.
+    fn cmd_set_entity_state(&mut self, id: EntityId, new_state: EntityState) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+        let entity = world
+            .get_entity_mut(id)
+            .ok_or(ApiError::EntityNotFound(id))?;
+
+        if entity.is_dead() {
+            return Err(ApiError::InvalidCommand(format!(
+                "Entity {} is dead and cannot change state",
+                id
+            )));
+        }
+
+        let old_state = entity.state;
+        if old_state == new_state {
+            return Ok(());
+        }
+        entity.state = new_state;
+
+        self.emit(EventData::EntityStateChanged {
+            entity_id: id,
+            old_state,
+            new_state,
+        });
+
+        Ok(())
+    }
+
+    fn cmd_set_entity_property(&mut self, cmd: SetEntityPropertyCmd) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+        let entity = world
+            .get_entity_mut(cmd.entity_id)
+            .ok_or(ApiError::EntityNotFound(cmd.entity_id))?;
+
+        if entity.is_dead() {
+            return Err(ApiError::InvalidCommand(format!(
+                "Entity {} is dead and cannot change properties",
+                cmd.entity_id
+            )));
+        }
+
+        let old_value = entity.properties.get(&cmd.property).unwrap_or_default();
+        entity
+            .properties
+            .set(&cmd.property, &cmd.value)
+            .map_err(ApiError::InvalidCommand)?;
+
+        if old_value != cmd.value {
+            self.emit(EventData::EntityPropertyChanged {
+                entity_id: cmd.entity_id,
+                property: cmd.property,
+                old_value,
+                new_value: cmd.value,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn cmd_set_entity_expiry(&mut self, cmd: SetEntityExpiryCmd) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+
+        let tick = world.current_tick;
+        if cmd.expires_at.is_some_and(|at| at <= tick) {
+            return Err(ApiError::InvalidCommand(format!(
+                "Expiry tick must be after current tick {}",
+                tick
+            )));
+        }
+
+        // Dead entities wait for cleanup, they never expire
+        if world.get_entity(cmd.entity_id).is_some_and(|e| e.is_dead()) {
+            return Err(ApiError::InvalidCommand(format!(
+                "Entity {} is dead and cannot expire",
+                cmd.entity_id
+            )));
+        }
+
+        let old_expires_at = world
+            .set_entity_expiry(cmd.entity_id, cmd.expires_at)
+            .ok_or(ApiError::EntityNotFound(cmd.entity_id))?;
+
+        if old_expires_at != cmd.expires_at {
+            self.emit(EventData::EntityExpiryChanged {
+                entity_id: cmd.entity_id,
+                old_expires_at,
+                new_expires_at: cmd.expires_at,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn cmd_attach_entity(&mut self, cmd: AttachEntityCmd) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+
+        let entity = world
+            .get_entity(cmd.entity_id)
+            .ok_or(ApiError::EntityNotFound(cmd.entity_id))?;
+        if world.get_entity(cmd.parent_id).is_none() {
+            return Err(ApiError::EntityNotFound(cmd.parent_id));
+        }
+
+        let attachment = Attachment {
+            parent: cmd.parent_id,
+            on_parent_despawn: cmd.on_parent_despawn,
+        };
+        if entity.attachment == Some(attachment) {
+            return Ok(());
+        }
+
+        let old_parent = world
+            .attach_entity(cmd.entity_id, attachment)
+            .map_err(ApiError::InvalidCommand)?;
+
+        self.emit(EventData::EntityAttached {
+            entity_id: cmd.entity_id,
+            attachment,
+            old_parent,
+        });
+
+        Ok(())
+    }
+
+    fn cmd_detach_entity(&mut self, id: EntityId) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
 
-        if world.remove_entity(id).is_none() {
+        if world.get_entity(id).is_none() {
             return Err(ApiError::EntityNotFound(id));
         }
+        let parent_id = world
+            .detach_entity(id)
+            .ok_or_else(|| ApiError::InvalidCommand(format!("Entity {} is not attached", id)))?;
 
-        self.emit(EventData::EntityDespawned {
+        self.emit(EventData::EntityDetached {
             entity_id: id,
-            reason: DespawnReason::Command,
+            parent_id,
         });
 
         Ok(())
@@ -371,148 +881,348 @@ impl<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> Simulation<R, C, E, S>
         Ok(())
     }
 
+    fn cmd_link_zones(&mut self, cmd: LinkZonesCmd) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+
+        for zone_id in [cmd.a, cmd.b] {
+            if !world.has_zone(zone_id) {
+                return Err(ApiError::ZoneNotFound(zone_id));
+            }
+        }
+
+        if world.link_zones(cmd.a, cmd.b) {
+            self.emit(EventData::ZonesLinked { a: cmd.a, b: cmd.b });
+        }
+
+        Ok(())
+    }
+
+    fn cmd_unlink_zones(&mut self, cmd: LinkZonesCmd) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+
+        if world.unlink_zones(cmd.a, cmd.b) {
+            self.emit(EventData::ZonesUnlinked { a: cmd.a, b: cmd.b });
+        }
+
+        Ok(())
+    }
+
+    fn cmd_add_portal(&mut self, cmd: AddPortalCmd) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+
+        for zone_id in [cmd.zone_id, cmd.portal.to.zone] {
+            if !world.has_zone(zone_id) {
+                return Err(ApiError::ZoneNotFound(zone_id));
+            }
+        }
+
+        let unchanged = world
+            .get_zone(cmd.zone_id)
+            .and_then(|z| z.portal_at(cmd.portal.from))
+            == Some(&cmd.portal);
+        if unchanged || !world.add_portal(cmd.zone_id, cmd.portal) {
+            return Ok(());
+        }
+
+        self.emit(EventData::PortalAdded {
+            zone_id: cmd.zone_id,
+            portal: cmd.portal,
+        });
+
+        Ok(())
+    }
+
+    fn cmd_remove_portal(&mut self, cmd: RemovePortalCmd) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+        let zone = world
+            .get_zone_mut(cmd.zone_id)
+            .ok_or(ApiError::ZoneNotFound(cmd.zone_id))?;
+
+        if zone.remove_portal(cmd.from).is_none() {
+            return Err(ApiError::InvalidCommand(format!(
+                "No portal at {} in zone {}",
+                cmd.from, cmd.zone_id
+            )));
+        }
+
+        self.emit(EventData::PortalRemoved {
+            zone_id: cmd.zone_id,
+            from: cmd.from,
+        });
+
+        Ok(())
+    }
+
+    fn cmd_set_zone_loaded(&mut self, zone_id: ZoneId, loaded: bool) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+        let current_tick = world.current_tick;
+        let zone = world
+            .get_zone_mut(zone_id)
+            .ok_or(ApiError::ZoneNotFound(zone_id))?;
+
+        // Already in the requested state: nothing happened
+        if zone.loaded == loaded {
+            return Ok(());
+        }
+
+        if !loaded {
+            zone.simulated_until = current_tick;
+        } else if self.coarse_interval > 0 && zone.simulated_until < current_tick {
+            // Catch up on the ticks since the last coarse step
+            self.run_coarse_steps(&[zone_id])?;
+        }
+
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+        if let Some(zone) = world.get_zone_mut(zone_id) {
+            zone.loaded = loaded;
+        }
+
+        if loaded {
+            self.emit(EventData::ZoneLoaded { zone_id });
+        } else {
+            self.emit(EventData::ZoneUnloaded { zone_id });
+        }
+
+        Ok(())
+    }
+
+    fn cmd_schedule(&mut self, cmd: ScheduleCmd) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+
+        if cmd.at <= world.current_tick {
+            return Err(ApiError::InvalidCommand(format!(
+                "Cannot schedule at {}: world is already at {}",
+                cmd.at, world.current_tick
+            )));
+        }
+
+        let timer = Timer {
+            id: world.timers.allocate_id(),
+            fire_at: cmd.at,
+            repeat_every: cmd.repeat_every,
+            command: *cmd.command,
+        };
+
+        let event = EventData::TimerScheduled {
+            timer_id: timer.id,
+            fire_at: timer.fire_at,
+            repeat_every: timer.repeat_every,
+            command: timer.command.clone(),
+        };
+        world.timers.insert(timer);
+        self.emit(event);
+
+        Ok(())
+    }
+
+    fn cmd_cancel_timer(&mut self, timer_id: TimerId) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+
+        if world.timers.remove(timer_id).is_none() {
+            return Err(ApiError::InvalidCommand(format!(
+                "Timer not found: {}",
+                timer_id
+            )));
+        }
+
+        self.emit(EventData::TimerCancelled { timer_id });
+
+        Ok(())
+    }
+
+    fn cmd_queue_input(&mut self, cmd: QueueInputCmd) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+
+        if cmd.at <= world.current_tick {
+            return Err(ApiError::InvalidCommand(format!(
+                "Input {}#{} targets {}: world is already at {}",
+                cmd.source, cmd.seq, cmd.at, world.current_tick
+            )));
+        }
+        if !world.inputs.accepts(cmd.source, cmd.seq) {
+            return Err(ApiError::InvalidCommand(format!(
+                "Input {}#{} already received (last seq {})",
+                cmd.source,
+                cmd.seq,
+                world.inputs.last_seq(cmd.source).unwrap_or(0)
+            )));
+        }
+
+        let input = QueuedInput {
+            source: cmd.source,
+            seq: cmd.seq,
+            at: cmd.at,
+            command: *cmd.command,
+        };
+
+        let event = EventData::InputQueued {
+            source: input.source,
+            seq: input.seq,
+            at: input.at,
+            command: input.command.clone(),
+        };
+        world.inputs.insert(input);
+        self.emit(event);
+
+        Ok(())
+    }
+
+    /// Apply every input due at `tick`, in (tick, source, seq) order.
+    /// A failing command is logged and leaves nothing behind (see "Command
+    /// implementations"); it does not abort the tick.
+    fn apply_inputs(&mut self, tick: Tick) -> ApiResult<()> {
+        let due = self
+            .world
+            .as_ref()
+            .ok_or(ApiError::NoWorldLoaded)?
+            .inputs
+            .due(tick);
+
+        for input in due {
+            let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+            world.inputs.remove(input.at, input.source, input.seq);
+            self.emit(EventData::InputApplied {
+                source: input.source,
+                seq: input.seq,
+                at: input.at,
+            });
+
+            if let Err(e) = self.execute(input.command) {
+                warn!(
+                    "Input {}#{} failed at {}: {}",
+                    input.source, input.seq, tick, e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run every timer due at `tick`, in (fire_at, timer_id) order.
+    /// A failing command is logged and leaves nothing behind (the timer
+    /// still counts as fired); it does not abort the tick.
+    fn fire_timers(&mut self, tick: Tick) -> ApiResult<()> {
+        let due = self
+            .world
+            .as_ref()
+            .ok_or(ApiError::NoWorldLoaded)?
+            .timers
+            .due(tick);
+
+        for timer_id in due {
+            let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+            let timer = match world.timers.get(timer_id) {
+                Some(t) => t.clone(),
+                None => continue, // Cancelled by an earlier timer this tick
+            };
+
+            let next_fire = timer.next_fire(tick);
+            world.timers.complete(timer_id, next_fire);
+            self.emit(EventData::TimerFired {
+                timer_id,
+                next_fire,
+            });
+
+            if let Err(e) = self.execute(timer.command) {
+                warn!("Timer {} command failed at {}: {}", timer_id, tick, e);
+            }
+        }
+
+        Ok(())
+    }
+
     // ========================================================================
-    // Tick systems (Phase 1: minimal rules)
+    // Tick systems
     // ========================================================================
 
-    /// Run all tick-based systems. Returns number of entities processed.
+    /// Run all registered tick systems. Returns number of entities processed.
     fn run_tick_systems(&mut self) -> ApiResult<u32> {
         let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
-        let mut processed = 0u32;
-
-        // Collect entity IDs to process (avoid borrow issues)
-        let entity_ids: Vec<EntityId> = world
-            .entities
-            .values()
-            .filter(|e| e.is_active())
-            .map(|e| e.id)
-            .collect();
+        let tick = world.current_tick;
+        let processed = world.simulated_entities().count() as u32;
+        let first_event = self.pending_events.len();
 
-        for entity_id in entity_ids {
-            // Get entity data
-            let (kind, health, amount) = {
-                let entity = match world.entities.get(&entity_id) {
-                    Some(e) => e,
-                    None => continue,
-                };
-                (
-                    entity.kind,
-                    entity.properties.health,
-                    entity.properties.amount,
-                )
-            };
+        // The world carries the root of all RNG streams
+        self.rng.restore(world.rng_state);
 
-            // Apply rules based on entity kind
-            match kind {
-                EntityKind::Resource => {
-                    // Resources degrade over time (simple rule)
-                    if let Some(amt) = amount {
-                        if amt > 0 && self.rng.chance(0.01) {
-                            // 1% chance per tick to lose 1 unit
-                            let new_amount = amt.saturating_sub(1);
-
-                            // Update entity
-                            if let Some(entity) = world.entities.get_mut(&entity_id) {
-                                entity.properties.amount = Some(new_amount);
-                            }
-
-                            self.pending_events.push(SimEvent::new(
-                                world.current_tick,
-                                EventData::ResourceDepleted {
-                                    entity_id,
-                                    amount: 1,
-                                    remaining: new_amount,
-                                },
-                            ));
-
-                            // If depleted, mark as dead
-                            if new_amount == 0 {
-                                if let Some(entity) = world.entities.get_mut(&entity_id) {
-                                    let old_state = entity.state;
-                                    entity.state = EntityState::Dead;
-
-                                    self.pending_events.push(SimEvent::new(
-                                        world.current_tick,
-                                        EventData::EntityStateChanged {
-                                            entity_id,
-                                            old_state,
-                                            new_state: EntityState::Dead,
-                                        },
-                                    ));
-                                }
-                            }
-                        }
-                    }
-                }
-                EntityKind::Creature => {
-                    // Creatures degrade health over time (hunger/decay)
-                    if let Some(hp) = health {
-                        if hp > 0 && self.rng.chance(0.005) {
-                            // 0.5% chance per tick
-                            let new_health = hp.saturating_sub(1);
-
-                            if let Some(entity) = world.entities.get_mut(&entity_id) {
-                                let old_health = hp;
-                                entity.properties.health = Some(new_health);
-
-                                self.pending_events.push(SimEvent::new(
-                                    world.current_tick,
-                                    EventData::EntityDegraded {
-                                        entity_id,
-                                        old_health,
-                                        new_health,
-                                    },
-                                ));
-                            }
-
-                            // If dead, mark as dead
-                            if new_health == 0 {
-                                if let Some(entity) = world.entities.get_mut(&entity_id) {
-                                    let old_state = entity.state;
-                                    entity.state = EntityState::Dead;
-
-                                    self.pending_events.push(SimEvent::new(
-                                        world.current_tick,
-                                        EventData::EntityStateChanged {
-                                            entity_id,
-                                            old_state,
-                                            new_state: EntityState::Dead,
-                                        },
-                                    ));
-                                }
-                            }
-                        }
-                    }
-                }
-                _ => {}
+        // Detach loaded zones (ZoneId order), each with a copy of the root
+        let loaded: Vec<ZoneId> = world
+            .zone_ids()
+            .filter(|&id| world.is_zone_loaded(id))
+            .collect();
+        let mut shards: Vec<ZoneShard> = Vec::with_capacity(loaded.len());
+        for zone_id in loaded {
+            if let Some(shard) = world.take_zone_shard(zone_id) {
+                shards.push(ZoneShard::new(zone_id, shard, &self.rng));
             }
+        }
 
-            processed += 1;
+        systems::run_zone_shards(&self.systems, &mut shards, tick, self.tick_threads);
+
+        // Merge back in ZoneId order, whatever the thread count
+        let mut worlds = Vec::with_capacity(shards.len());
+        for shard in shards {
+            let zones = std::iter::repeat_n(Some(shard.zone_id), shard.events.len());
+            self.pending_zones.extend(zones);
+            self.pending_events.extend(shard.events);
+            worlds.push(shard.world);
         }
+        world.merge_zone_shards(worlds);
 
-        // Clean up dead entities periodically (every 100 ticks)
-        if world.current_tick.as_u64() % 100 == 0 {
-            let dead_ids: Vec<EntityId> = world
-                .entities
-                .values()
-                .filter(|e| e.is_dead())
-                .map(|e| e.id)
-                .collect();
+        // Record entity changes exactly as replay will (see apply_event)
+        for event in &self.pending_events[first_event..] {
+            world.touch_entity(&event.data, event.tick);
+        }
 
-            for id in dead_ids {
-                if world.remove_entity(id).is_some() {
-                    self.pending_events.push(SimEvent::new(
-                        world.current_tick,
-                        EventData::EntityDespawned {
-                            entity_id: id,
-                            reason: DespawnReason::Death,
-                        },
-                    ));
-                }
+        Ok(processed)
+    }
+
+    /// Advance unloaded zones to the current tick with the coarse models of
+    /// the systems, in the given order.
+    fn run_coarse_steps(&mut self, zones: &[ZoneId]) -> ApiResult<()> {
+        let world = self.world.as_mut().ok_or(ApiError::NoWorldLoaded)?;
+        let tick = world.current_tick;
+        let first_event = self.pending_events.len();
+
+        self.rng.restore(world.rng_state);
+
+        let mut worlds = Vec::with_capacity(zones.len());
+        for &zone_id in zones {
+            let from = match world.get_zone(zone_id) {
+                Some(zone) => zone.simulated_until,
+                None => continue,
+            };
+            let Some(shard) = world.take_zone_shard(zone_id) else {
+                continue;
+            };
+
+            let mut shard = ZoneShard::new(zone_id, shard, &self.rng);
+            shard.run_coarse(&self.systems, tick, tick.as_u64() - from.as_u64());
+            if let Some(zone) = shard.world.get_zone_mut(zone_id) {
+                zone.simulated_until = tick;
             }
+
+            self.pending_events.push(SimEvent::new(
+                tick,
+                EventData::ZoneCoarseStepped {
+                    zone_id,
+                    from,
+                    to: tick,
+                },
+            ));
+            let zones = std::iter::repeat_n(Some(zone_id), shard.events.len() + 1);
+            self.pending_zones.extend(zones);
+            self.pending_events.extend(shard.events);
+            worlds.push(shard.world);
         }
+        world.merge_zone_shards(worlds);
 
-        Ok(processed)
+        // Record entity changes exactly as replay will (see apply_event)
+        for event in &self.pending_events[first_event..] {
+            world.touch_entity(&event.data, event.tick);
+        }
+
+        Ok(())
     }
 
     // ========================================================================
@@ -520,12 +1230,27 @@ impl<R: IRng, C: ISimClock, E: IEventLog, S: IWorldStore> Simulation<R, C, E, S>
     // ========================================================================
 
     fn emit(&mut self, data: EventData) {
+        let zone = data
+            .entity_id()
+            .and_then(|id| self.world.as_ref()?.get_entity(id))
+            .map(|e| e.position.zone);
+        self.emit_in_zone(data, zone);
+    }
+
+    /// Emit an event whose entity was in `zone` (it may be gone already).
+    fn emit_in_zone(&mut self, data: EventData, zone: Option<ZoneId>) {
         let tick = self
             .world
             .as_ref()
             .map(|w| w.current_tick)
             .unwrap_or(Tick::ZERO);
+
+        if let Some(world) = self.world.as_mut() {
+            world.touch_entity(&data, tick);
+        }
+
         self.pending_events.push(SimEvent::new(tick, data));
+        self.pending_zones.push(zone);
     }
 }
 
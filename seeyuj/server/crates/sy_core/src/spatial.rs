@@ -0,0 +1,287 @@
+//! # Spatial Index
+//!
+//! Per-zone uniform grid over local positions.
+//!
+//! ## Design
+//! - Entities are bucketed into cubic cells of `CELL_SIZE` units
+//! - The index is derived data: it is not serialized, `World` keeps it in
+//!   sync on spawn, despawn and move, and rebuilds it after loading
+//! - Queries only visit the cells overlapping the query volume
+//!
+//! ## Determinism
+//! Cells and buckets are BTree-ordered and every query returns results in
+//! a fixed order (EntityId, or distance then EntityId for nearest queries).
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Bound;
+
+use sy_types::{EntityId, Position};
+
+/// Edge length of a grid cell, in local units.
+pub const CELL_SIZE: i32 = 16;
+
+type Cell = (i32, i32, i32);
+
+fn cell_of(pos: Position) -> Cell {
+    (
+        pos.x.div_euclid(CELL_SIZE),
+        pos.y.div_euclid(CELL_SIZE),
+        pos.z.div_euclid(CELL_SIZE),
+    )
+}
+
+/// Grid index of the entities of one zone.
+#[derive(Debug, Clone, Default)]
+pub struct SpatialIndex {
+    /// Occupied cells -> entity IDs
+    cells: BTreeMap<Cell, BTreeSet<EntityId>>,
+    /// Indexed position of every entity
+    positions: BTreeMap<EntityId, Position>,
+}
+
+impl SpatialIndex {
+    pub fn new() -> Self {
+        SpatialIndex::default()
+    }
+
+    /// Insert or move an entity.
+    pub fn insert(&mut self, id: EntityId, pos: Position) {
+        if let Some(old) = self.positions.insert(id, pos) {
+            if cell_of(old) == cell_of(pos) {
+                return;
+            }
+            self.remove_from_cell(cell_of(old), id);
+        }
+        self.cells.entry(cell_of(pos)).or_default().insert(id);
+    }
+
+    /// Remove an entity. Returns true if it was indexed.
+    pub fn remove(&mut self, id: EntityId) -> bool {
+        match self.positions.remove(&id) {
+            Some(pos) => {
+                self.remove_from_cell(cell_of(pos), id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Indexed position of an entity.
+    pub fn position(&self, id: EntityId) -> Option<Position> {
+        self.positions.get(&id).copied()
+    }
+
+    /// Iterate indexed entities and their positions, by EntityId.
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, Position)> + '_ {
+        self.positions.iter().map(|(id, pos)| (*id, *pos))
+    }
+
+    /// Indexed entities after `after` (all if None), by EntityId.
+    pub fn ids_after(&self, after: Option<EntityId>) -> impl Iterator<Item = EntityId> + '_ {
+        let start = after.map_or(Bound::Unbounded, Bound::Excluded);
+        self.positions
+            .range((start, Bound::Unbounded))
+            .map(|(id, _)| *id)
+    }
+
+    /// Whether every entity sits in the cell of its indexed position,
+    /// and in no other cell.
+    pub fn cells_consistent(&self) -> bool {
+        let bucketed: usize = self.cells.values().map(BTreeSet::len).sum();
+        bucketed == self.positions.len()
+            && self.positions.iter().all(|(id, pos)| {
+                self.cells
+                    .get(&cell_of(*pos))
+                    .is_some_and(|c| c.contains(id))
+            })
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Entities inside the axis-aligned box [min, max] (inclusive), by EntityId.
+    pub fn in_box(&self, min: Position, max: Position) -> Vec<EntityId> {
+        let mut found = self.collect_box(min, max);
+        found.sort();
+        found
+    }
+
+    /// Entities within Euclidean distance `radius` of `center`, by EntityId.
+    pub fn within_radius(&self, center: Position, radius: u32) -> Vec<EntityId> {
+        let r2 = radius as u128 * radius as u128;
+        let (min, max) = bounds(center, radius as i64);
+        let mut found: Vec<EntityId> = self
+            .collect_box(min, max)
+            .into_iter()
+            .filter(|id| self.positions[id].distance_squared(&center) <= r2)
+            .collect();
+        found.sort();
+        found
+    }
+
+    /// Up to `n` entities accepted by `filter`, closest to `center` first
+    /// (ties broken by EntityId).
+    pub fn nearest(
+        &self,
+        center: Position,
+        n: usize,
+        filter: impl Fn(EntityId) -> bool,
+    ) -> Vec<EntityId> {
+        if n == 0 || self.is_empty() {
+            return Vec::new();
+        }
+
+        // Grow a search cube until it holds n matches within its inscribed
+        // sphere (nothing outside the cube can be closer), or covers everything.
+        let mut half = CELL_SIZE as i64;
+        loop {
+            let (min, max) = bounds(center, half);
+            let candidates = self.collect_box(min, max);
+            let covers_all = candidates.len() == self.positions.len();
+
+            let mut ranked: Vec<(u128, EntityId)> = candidates
+                .into_iter()
+                .filter(|id| filter(*id))
+                .map(|id| (self.positions[&id].distance_squared(&center), id))
+                .collect();
+            ranked.sort();
+
+            let r2 = half as u128 * half as u128;
+            let confirmed = ranked.iter().filter(|(d2, _)| *d2 <= r2).count();
+            if confirmed >= n || covers_all {
+                ranked.truncate(n);
+                return ranked.into_iter().map(|(_, id)| id).collect();
+            }
+            half *= 2;
+        }
+    }
+
+    fn collect_box(&self, min: Position, max: Position) -> Vec<EntityId> {
+        if min.x > max.x || min.y > max.y || min.z > max.z {
+            return Vec::new();
+        }
+        let (lo, hi) = (cell_of(min), cell_of(max));
+        let span = |a: i32, b: i32| (b as i64 - a as i64 + 1) as u128;
+        let box_cells = span(lo.0, hi.0) * span(lo.1, hi.1) * span(lo.2, hi.2);
+
+        let inside = |pos: &Position| {
+            (min.x..=max.x).contains(&pos.x)
+                && (min.y..=max.y).contains(&pos.y)
+                && (min.z..=max.z).contains(&pos.z)
+        };
+
+        let mut found = Vec::new();
+        if box_cells > self.cells.len() as u128 {
+            // Large box: walk occupied cells instead of the cell range
+            for (cell, ids) in &self.cells {
+                if (lo.0..=hi.0).contains(&cell.0)
+                    && (lo.1..=hi.1).contains(&cell.1)
+                    && (lo.2..=hi.2).contains(&cell.2)
+                {
+                    found.extend(ids.iter().filter(|id| inside(&self.positions[id])));
+                }
+            }
+        } else {
+            for cx in lo.0..=hi.0 {
+                for cy in lo.1..=hi.1 {
+                    for cz in lo.2..=hi.2 {
+                        if let Some(ids) = self.cells.get(&(cx, cy, cz)) {
+                            found.extend(ids.iter().filter(|id| inside(&self.positions[id])));
+                        }
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    fn remove_from_cell(&mut self, cell: Cell, id: EntityId) {
+        if let Some(ids) = self.cells.get_mut(&cell) {
+            ids.remove(&id);
+            if ids.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+}
+
+/// Cube of half-size `half` around `center`, clamped to the i32 range.
+fn bounds(center: Position, half: i64) -> (Position, Position) {
+    let clamp = |v: i64| v.clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+    let axis = |c: i32| (clamp(c as i64 - half), clamp(c as i64 + half));
+    let (x0, x1) = axis(center.x);
+    let (y0, y1) = axis(center.y);
+    let (z0, z1) = axis(center.z);
+    (Position::new(x0, y0, z0), Position::new(x1, y1, z1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(points: &[(i32, i32)]) -> SpatialIndex {
+        let mut index = SpatialIndex::new();
+        for (i, (x, y)) in points.iter().enumerate() {
+            index.insert(EntityId::new(i as u64 + 1), Position::new(*x, *y, 0));
+        }
+        index
+    }
+
+    fn ids(raw: &[u64]) -> Vec<EntityId> {
+        raw.iter().map(|&i| EntityId::new(i)).collect()
+    }
+
+    #[test]
+    fn box_and_radius_queries() {
+        let index = index(&[(0, 0), (3, 4), (-20, 0), (100, 100)]);
+
+        assert_eq!(index.within_radius(Position::ORIGIN, 5), ids(&[1, 2]));
+        assert_eq!(index.within_radius(Position::ORIGIN, 4), ids(&[1]));
+        assert_eq!(
+            index.in_box(Position::new(-20, -1, 0), Position::new(3, 3, 0)),
+            ids(&[1, 3])
+        );
+        assert_eq!(
+            index.in_box(
+                Position::new(i32::MIN, i32::MIN, i32::MIN),
+                Position::new(i32::MAX, i32::MAX, i32::MAX)
+            ),
+            ids(&[1, 2, 3, 4])
+        );
+    }
+
+    #[test]
+    fn nearest_ranks_by_distance_then_id() {
+        let index = index(&[(10, 0), (-10, 0), (1000, 0), (2, 0)]);
+
+        assert_eq!(
+            index.nearest(Position::ORIGIN, 3, |_| true),
+            ids(&[4, 1, 2])
+        );
+        assert_eq!(
+            index.nearest(Position::ORIGIN, 2, |id| id.as_u64() != 4),
+            ids(&[1, 2])
+        );
+        assert_eq!(
+            index.nearest(Position::ORIGIN, 10, |id| id.as_u64() == 3),
+            ids(&[3])
+        );
+    }
+
+    #[test]
+    fn move_and_remove_keep_cells_consistent() {
+        let mut index = index(&[(0, 0), (1, 1)]);
+        index.insert(EntityId::new(1), Position::new(500, 0, 0));
+        assert!(index.remove(EntityId::new(2)));
+        assert!(!index.remove(EntityId::new(2)));
+
+        assert!(index.within_radius(Position::ORIGIN, 10).is_empty());
+        assert_eq!(index.within_radius(Position::new(500, 0, 0), 0), ids(&[1]));
+        assert_eq!(index.cells.len(), 1);
+    }
+}
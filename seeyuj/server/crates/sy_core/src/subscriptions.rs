@@ -0,0 +1,260 @@
+//! # Subscriptions
+//!
+//! In-process event bus: subscribers registered on the `Simulation`
+//! receive the events it persists.
+//!
+//! ## Design
+//! - Delivery happens after a command's events reached the WAL (they carry
+//!   their `event_id`); events of a failed command are never delivered
+//! - Each subscriber sees events in `event_id` order; subscribers are
+//!   called in `SubscriptionId` order for each event
+//! - Filters select by event type, zone and entity; replayed events
+//!   (crash recovery) are not delivered again
+//! - Subscribers observe: they cannot change the world or the events, so
+//!   they never affect determinism
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use sy_api::events::SimEvent;
+use sy_types::{EntityId, ZoneId};
+
+/// Identifier of a subscription, unique within a `Simulation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SubscriptionId(u64);
+
+impl SubscriptionId {
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for SubscriptionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "S{}", self.0)
+    }
+}
+
+/// Receives persisted events.
+pub trait IEventSubscriber: Send {
+    /// Called once per matching event, in `event_id` order.
+    fn on_event(&mut self, event: &SimEvent);
+}
+
+impl<F: FnMut(&SimEvent) + Send> IEventSubscriber for F {
+    fn on_event(&mut self, event: &SimEvent) {
+        self(event)
+    }
+}
+
+/// Which events a subscriber receives.
+///
+/// Each criterion left empty matches everything; an event must match every
+/// non-empty criterion (any of its values).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    /// Event type names (`EventData::type_name`)
+    pub types: BTreeSet<String>,
+    /// Zones named by the event, or holding its entity when it was emitted
+    pub zones: BTreeSet<ZoneId>,
+    /// Entity the event is about
+    pub entities: BTreeSet<EntityId>,
+}
+
+impl EventFilter {
+    /// Match every event.
+    pub fn all() -> Self {
+        EventFilter::default()
+    }
+
+    /// Also match events of this type (e.g. "EntityMoved").
+    pub fn with_type(mut self, type_name: impl Into<String>) -> Self {
+        self.types.insert(type_name.into());
+        self
+    }
+
+    /// Also match events in this zone.
+    pub fn with_zone(mut self, zone_id: ZoneId) -> Self {
+        self.zones.insert(zone_id);
+        self
+    }
+
+    /// Also match events about this entity.
+    pub fn with_entity(mut self, entity_id: EntityId) -> Self {
+        self.entities.insert(entity_id);
+        self
+    }
+
+    /// Whether `event` matches. `entity_zone` is the zone of its entity
+    /// when it was emitted, if any.
+    pub fn matches(&self, event: &SimEvent, entity_zone: Option<ZoneId>) -> bool {
+        if !self.types.is_empty() && !self.types.contains(event.data.type_name()) {
+            return false;
+        }
+        if !self.entities.is_empty()
+            && !event
+                .data
+                .entity_id()
+                .is_some_and(|id| self.entities.contains(&id))
+        {
+            return false;
+        }
+        if !self.zones.is_empty() {
+            let in_zone = entity_zone.is_some_and(|z| self.zones.contains(&z))
+                || event.data.zone_ids().iter().any(|z| self.zones.contains(z));
+            if !in_zone {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+struct Subscription {
+    filter: EventFilter,
+    subscriber: Box<dyn IEventSubscriber>,
+}
+
+/// Registered subscribers of a `Simulation`.
+#[derive(Default)]
+pub struct EventBus {
+    next_id: u64,
+    subscriptions: BTreeMap<SubscriptionId, Subscription>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        EventBus::default()
+    }
+
+    /// Register a subscriber; it receives events persisted from now on.
+    pub fn subscribe(
+        &mut self,
+        filter: EventFilter,
+        subscriber: Box<dyn IEventSubscriber>,
+    ) -> SubscriptionId {
+        self.next_id += 1;
+        let id = SubscriptionId(self.next_id);
+        self.subscriptions
+            .insert(id, Subscription { filter, subscriber });
+        id
+    }
+
+    /// Remove a subscriber. Returns false if it was not registered.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        self.subscriptions.remove(&id).is_some()
+    }
+
+    /// Number of subscribers.
+    pub fn len(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+
+    /// Deliver persisted events (in `event_id` order) to matching
+    /// subscribers. `entity_zones[i]` is the zone of the entity of
+    /// `events[i]` when it was emitted.
+    pub fn publish(&mut self, events: &[SimEvent], entity_zones: &[Option<ZoneId>]) {
+        for (i, event) in events.iter().enumerate() {
+            let zone = entity_zones.get(i).copied().flatten();
+            for subscription in self.subscriptions.values_mut() {
+                if subscription.filter.matches(event, zone) {
+                    subscription.subscriber.on_event(event);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventBus")
+            .field("subscriptions", &self.subscriptions.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use sy_api::events::EventData;
+    use sy_types::{EventId, Position, Tick, WorldPos};
+
+    fn moved(id: u64, entity: u64, from: u32, to: u32) -> SimEvent {
+        SimEvent::with_id(
+            EventId::new(id),
+            Tick(1),
+            EventData::EntityMoved {
+                entity_id: EntityId::new(entity),
+                from: WorldPos::new(ZoneId::new(from), Position::ORIGIN),
+                to: WorldPos::new(ZoneId::new(to), Position::ORIGIN),
+            },
+        )
+    }
+
+    #[test]
+    fn filters_combine_criteria() {
+        let event = moved(1, 7, 0, 2);
+        assert!(EventFilter::all().matches(&event, None));
+        assert!(EventFilter::all()
+            .with_type("TickProcessed")
+            .with_type("EntityMoved")
+            .matches(&event, None));
+        assert!(!EventFilter::all()
+            .with_type("EntityMoved")
+            .with_entity(EntityId::new(8))
+            .matches(&event, None));
+
+        // Zones named by the event, or the entity's zone at emission
+        assert!(EventFilter::all()
+            .with_zone(ZoneId::new(2))
+            .matches(&event, None));
+        let depleted = SimEvent::with_id(
+            EventId::new(2),
+            Tick(1),
+            EventData::ResourceDepleted {
+                entity_id: EntityId::new(7),
+                amount: 1,
+                remaining: 0,
+            },
+        );
+        let zone_3 = EventFilter::all().with_zone(ZoneId::new(3));
+        assert!(zone_3.matches(&depleted, Some(ZoneId::new(3))));
+        assert!(!zone_3.matches(&depleted, Some(ZoneId::new(2))));
+        assert!(!zone_3.matches(&depleted, None));
+    }
+
+    #[test]
+    fn type_names_match_serialized_tags() {
+        let event = moved(1, 7, 0, 2);
+        let json = serde_json::to_value(&event.data).unwrap();
+        assert!(json.get(event.data.type_name()).is_some());
+    }
+
+    #[test]
+    fn subscribers_receive_matching_events_in_order() {
+        let mut bus = EventBus::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        let log = Arc::clone(&seen);
+        let id = bus.subscribe(
+            EventFilter::all().with_entity(EntityId::new(7)),
+            Box::new(move |event: &SimEvent| log.lock().unwrap().push(event.event_id)),
+        );
+
+        bus.publish(&[moved(1, 7, 0, 0), moved(2, 8, 0, 0)], &[]);
+        bus.publish(&[moved(3, 7, 0, 0)], &[]);
+        assert!(bus.unsubscribe(id));
+        bus.publish(&[moved(4, 7, 0, 0)], &[]);
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![EventId::new(1), EventId::new(3)]
+        );
+        assert!(!bus.unsubscribe(id));
+    }
+}
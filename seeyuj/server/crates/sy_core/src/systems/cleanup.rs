@@ -0,0 +1,61 @@
+//! # Dead Entity Cleanup
+//!
+//! Periodically removes entities marked Dead from loaded zones.
+
+use sy_api::events::DespawnReason;
+use sy_types::EntityId;
+
+use super::{order, ITickSystem, SystemId, TickContext};
+use crate::world::World;
+
+/// Despawns dead entities every `interval` ticks.
+pub struct DeadEntityCleanupSystem {
+    /// Cleanup period in ticks (0 = never)
+    pub interval: u64,
+}
+
+impl DeadEntityCleanupSystem {
+    pub const ID: SystemId = "dead_entity_cleanup";
+    pub const DEFAULT_INTERVAL: u64 = 100;
+}
+
+impl Default for DeadEntityCleanupSystem {
+    fn default() -> Self {
+        DeadEntityCleanupSystem {
+            interval: Self::DEFAULT_INTERVAL,
+        }
+    }
+}
+
+impl ITickSystem for DeadEntityCleanupSystem {
+    fn id(&self) -> SystemId {
+        Self::ID
+    }
+
+    fn order(&self) -> i32 {
+        order::DEAD_ENTITY_CLEANUP
+    }
+
+    fn run(&self, world: &mut World, ctx: &mut TickContext<'_>) {
+        if self.interval == 0 || !ctx.tick.as_u64().is_multiple_of(self.interval) {
+            return;
+        }
+
+        let dead_ids: Vec<EntityId> = world
+            .entities
+            .values()
+            .filter(|e| e.is_dead() && world.is_zone_loaded(e.position.zone))
+            .map(|e| e.id)
+            .collect();
+
+        for id in dead_ids {
+            // Already gone if despawned along with its parent
+            for event in world
+                .despawn_entity(id, DespawnReason::Death)
+                .unwrap_or_default()
+            {
+                ctx.emit(event);
+            }
+        }
+    }
+}
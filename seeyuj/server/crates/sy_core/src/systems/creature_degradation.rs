@@ -0,0 +1,95 @@
+//! # Creature Degradation
+//!
+//! Creatures lose health over time (hunger/decay).
+//! A creature reaching zero health is marked Dead.
+//! Coarse model (unloaded zones): the expected losses over the period.
+
+use sy_api::events::EventData;
+use sy_types::{EntityId, EntityKind, EntityState, Probability};
+
+use super::{active_entity_ids, order, simulated_entity_ids, ITickSystem, SystemId, TickContext};
+use crate::world::World;
+
+/// Creatures degrade health over time.
+pub struct CreatureDegradationSystem;
+
+impl CreatureDegradationSystem {
+    pub const ID: SystemId = "creature_degradation";
+    /// Chance per tick to lose one health point
+    pub const CHANCE: Probability = Probability::new(1, 200);
+
+    /// Remove up to `losses` health points, marking the creature Dead at zero.
+    fn degrade(world: &mut World, ctx: &mut TickContext<'_>, entity_id: EntityId, losses: u64) {
+        let entity = match world.entities.get_mut(&entity_id) {
+            Some(e) => e,
+            None => return,
+        };
+
+        let old_health = entity.properties.health.unwrap_or(0);
+        let new_health = old_health - losses.min(old_health as u64) as u32;
+        if new_health == old_health {
+            return;
+        }
+
+        entity.properties.health = Some(new_health);
+
+        ctx.emit(EventData::EntityDegraded {
+            entity_id,
+            old_health,
+            new_health,
+        });
+
+        // If dead, mark as dead
+        if new_health == 0 {
+            let old_state = world.mark_dead(entity_id).unwrap_or(EntityState::Dead);
+
+            ctx.emit(EventData::EntityStateChanged {
+                entity_id,
+                old_state,
+                new_state: EntityState::Dead,
+            });
+        }
+    }
+
+    fn has_health(world: &World, entity_id: EntityId) -> bool {
+        world
+            .entities
+            .get(&entity_id)
+            .is_some_and(|e| e.properties.health.unwrap_or(0) > 0)
+    }
+}
+
+impl ITickSystem for CreatureDegradationSystem {
+    fn id(&self) -> SystemId {
+        Self::ID
+    }
+
+    fn order(&self) -> i32 {
+        order::CREATURE_DEGRADATION
+    }
+
+    fn run(&self, world: &mut World, ctx: &mut TickContext<'_>) {
+        for entity_id in simulated_entity_ids(world, EntityKind::Creature) {
+            if !Self::has_health(world, entity_id)
+                || !ctx.entity_rng(entity_id).chance(Self::CHANCE)
+            {
+                continue;
+            }
+
+            Self::degrade(world, ctx, entity_id, 1);
+        }
+    }
+
+    fn run_coarse(&self, world: &mut World, ctx: &mut TickContext<'_>, ticks: u64) {
+        for entity_id in active_entity_ids(world, EntityKind::Creature) {
+            if !Self::has_health(world, entity_id) {
+                continue;
+            }
+
+            let losses = ctx
+                .entity_rng(entity_id)
+                .expected_successes(ticks, Self::CHANCE);
+            Self::degrade(world, ctx, entity_id, losses);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! # Dormancy
+//!
+//! Optional rules that put idle entities to sleep and wake them up again.
+//! Neither rule is registered by default.
+//!
+//! ## Change tracking
+//! Both rules rely on `Entity::last_changed` and `Entity::last_active`,
+//! which are updated from the event stream (live and on replay), so they
+//! stay deterministic across restarts and crash recovery.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use sy_api::events::EventData;
+use sy_types::{EntityId, EntityState, Position, ZoneId};
+
+use super::{order, ITickSystem, SystemId, TickContext};
+use crate::world::World;
+
+/// Puts entities to sleep after `idle_ticks` ticks without any event.
+pub struct IdleDormancySystem {
+    /// Ticks without change before an entity becomes Dormant (0 = never)
+    pub idle_ticks: u64,
+}
+
+impl IdleDormancySystem {
+    pub const ID: SystemId = "idle_dormancy";
+
+    pub fn new(idle_ticks: u64) -> Self {
+        IdleDormancySystem { idle_ticks }
+    }
+}
+
+impl ITickSystem for IdleDormancySystem {
+    fn id(&self) -> SystemId {
+        Self::ID
+    }
+
+    fn order(&self) -> i32 {
+        order::IDLE_DORMANCY
+    }
+
+    fn run(&self, world: &mut World, ctx: &mut TickContext<'_>) {
+        if self.idle_ticks == 0 {
+            return;
+        }
+
+        // Entities named by this tick's events changed now, even though
+        // their last_changed is only bumped after the merge
+        let changed_now: BTreeSet<EntityId> = ctx
+            .events()
+            .iter()
+            .filter_map(|e| e.data.entity_id())
+            .collect();
+
+        let now = ctx.tick.as_u64();
+        let idle: Vec<EntityId> = world
+            .simulated_entities()
+            .filter(|e| now.saturating_sub(e.last_changed.as_u64()) >= self.idle_ticks)
+            .filter(|e| !changed_now.contains(&e.id))
+            .map(|e| e.id)
+            .collect();
+
+        for entity_id in idle {
+            set_state(world, ctx, entity_id, EntityState::Dormant);
+        }
+    }
+}
+
+/// Wakes dormant entities when an active entity within `radius`
+/// (Manhattan distance, same zone) changed during the previous tick.
+/// Wakes do not count as changes, so wakes never chain.
+pub struct NeighbourWakeSystem {
+    /// Wake radius in local units
+    pub radius: u32,
+}
+
+impl NeighbourWakeSystem {
+    pub const ID: SystemId = "neighbour_wake";
+
+    pub fn new(radius: u32) -> Self {
+        NeighbourWakeSystem { radius }
+    }
+}
+
+impl ITickSystem for NeighbourWakeSystem {
+    fn id(&self) -> SystemId {
+        Self::ID
+    }
+
+    fn order(&self) -> i32 {
+        order::NEIGHBOUR_WAKE
+    }
+
+    fn run(&self, world: &mut World, ctx: &mut TickContext<'_>) {
+        let previous = match ctx.tick.as_u64().checked_sub(1) {
+            Some(t) => t,
+            None => return,
+        };
+
+        // Active entities that changed since the last tick, grouped by zone.
+        // Dormant entities are not triggers, and neither is being woken up:
+        // otherwise a wake would spread through a dormant cluster, one
+        // radius per tick.
+        let mut changed: BTreeMap<ZoneId, Vec<(EntityId, Position)>> = BTreeMap::new();
+        for e in world.simulated_entities() {
+            if e.last_active.as_u64() >= previous {
+                changed
+                    .entry(e.position.zone)
+                    .or_default()
+                    .push((e.id, e.position.pos));
+            }
+        }
+        if changed.is_empty() {
+            return;
+        }
+
+        // Look up neighbours through the spatial index (box around each
+        // trigger, computed in i64 and clamped to the i32 grid)
+        let r = self.radius as i64;
+        let clamp = |v: i64| v.clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+        let mut to_wake: BTreeSet<EntityId> = BTreeSet::new();
+        for (zone, triggers) in &changed {
+            for (trigger, pos) in triggers {
+                let min = Position::new(
+                    clamp(pos.x as i64 - r),
+                    clamp(pos.y as i64 - r),
+                    clamp(pos.z as i64 - r),
+                );
+                let max = Position::new(
+                    clamp(pos.x as i64 + r),
+                    clamp(pos.y as i64 + r),
+                    clamp(pos.z as i64 + r),
+                );
+                for id in world.entities_in_box(*zone, min, max) {
+                    let Some(e) = world.get_entity(id) else {
+                        continue;
+                    };
+                    if id != *trigger
+                        && e.is_dormant()
+                        && pos.manhattan_distance(&e.position.pos) <= self.radius as u64
+                    {
+                        to_wake.insert(id);
+                    }
+                }
+            }
+        }
+
+        for entity_id in to_wake {
+            set_state(world, ctx, entity_id, EntityState::Active);
+        }
+    }
+}
+
+fn set_state(
+    world: &mut World,
+    ctx: &mut TickContext<'_>,
+    entity_id: EntityId,
+    new_state: EntityState,
+) {
+    if let Some(entity) = world.get_entity_mut(entity_id) {
+        let old_state = entity.state;
+        entity.state = new_state;
+        ctx.emit(EventData::EntityStateChanged {
+            entity_id,
+            old_state,
+            new_state,
+        });
+    }
+}
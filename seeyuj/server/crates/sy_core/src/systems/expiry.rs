@@ -0,0 +1,46 @@
+//! # Entity Expiry
+//!
+//! Despawns entities whose `expires_at` tick has been reached.
+//! Driven by the world's expiry schedule: cost is proportional to the
+//! number of due entities, not to the total entity count.
+
+use sy_api::events::DespawnReason;
+
+use super::{order, ITickSystem, SystemId, TickContext};
+use crate::world::World;
+
+/// Despawns expired entities in loaded zones.
+/// Entities in unloaded zones stay scheduled and expire once the zone loads.
+pub struct EntityExpirySystem;
+
+impl EntityExpirySystem {
+    pub const ID: SystemId = "entity_expiry";
+}
+
+impl ITickSystem for EntityExpirySystem {
+    fn id(&self) -> SystemId {
+        Self::ID
+    }
+
+    fn order(&self) -> i32 {
+        order::ENTITY_EXPIRY
+    }
+
+    fn run(&self, world: &mut World, ctx: &mut TickContext<'_>) {
+        for id in world.expired_entities(ctx.tick) {
+            let loaded = world
+                .get_entity(id)
+                .is_some_and(|e| world.is_zone_loaded(e.position.zone));
+            if !loaded {
+                continue;
+            }
+
+            for event in world
+                .despawn_entity(id, DespawnReason::Expired)
+                .unwrap_or_default()
+            {
+                ctx.emit(event);
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! # Systems
+//!
+//! Pluggable tick systems: the systemic rules run on every tick.
+//!
+//! ## Design
+//! - Each system declares a stable ID and an ordering key
+//! - The Simulation runs registered systems sorted by (order, id)
+//! - Systems mutate the World and emit events through a `TickContext`
+//! - Systems must only draw randomness from the injected RNG
+//! - Entities in unloaded zones are not ticked: they are frozen, or
+//!   advanced now and then by the coarse model (`run_coarse`, see
+//!   "Level of detail" in `Simulation`)
+//!
+//! ## Zone shards
+//! Each tick, every loaded zone is detached into a shard (a `World` holding
+//! only that zone and its entities) and the full system list runs on each
+//! shard, possibly on several threads. Consequently systems:
+//! - only see one zone at a time
+//! - must keep no state of their own (it would be neither shared between
+//!   shards nor persisted): any state belongs in the World
+//! - must not spawn entities (entity IDs are allocated by commands)
+//!
+//! ## Determinism
+//! Execution order never depends on registration order: two simulations
+//! with the same set of systems always run them in the same sequence.
+//! Randomness comes from streams split off the world root generator
+//! (seeded from the world seed, state kept in `World::rng_state`) and keyed
+//! by (system, entity, tick) or (system, zone, tick): adding an entity or a
+//! rule never shifts the numbers drawn by the others. Shard events are
+//! merged in ZoneId order, so results do not depend on the number of threads.
+
+mod cleanup;
+mod creature_degradation;
+mod dormancy;
+mod expiry;
+mod movement;
+mod resource_depletion;
+mod shards;
+
+pub use cleanup::DeadEntityCleanupSystem;
+pub use creature_degradation::CreatureDegradationSystem;
+pub use dormancy::{IdleDormancySystem, NeighbourWakeSystem};
+pub use expiry::EntityExpirySystem;
+pub use movement::MovementSystem;
+pub use resource_depletion::ResourceDepletionSystem;
+pub(crate) use shards::{run_zone_shards, ZoneShard};
+
+use sy_api::events::{EventData, SimEvent};
+use sy_types::{EntityId, EntityKind, Tick, ZoneId};
+
+use crate::ports::{name_key, stream_key, IRng};
+use crate::world::World;
+
+/// Stable identifier of a tick system.
+pub type SystemId = &'static str;
+
+/// Domain tags keeping entity and zone stream keys apart.
+const ENTITY_STREAM: u64 = 1;
+const ZONE_STREAM: u64 = 2;
+
+/// Per-tick context handed to every system.
+///
+/// Randomness comes from streams split off the world root generator:
+/// - `entity_rng(id)`: keyed by (system, entity, tick)
+/// - `rng()`: keyed by (system, zone, tick), for draws not tied to an entity
+pub struct TickContext<'a> {
+    /// Tick being processed
+    pub tick: Tick,
+    /// Zone being processed
+    zone: ZoneId,
+    /// World root generator (never advanced)
+    root: &'a dyn IRng,
+    /// System currently running
+    system: SystemId,
+    /// Stream of the current system in this zone
+    rng: Box<dyn IRng>,
+    /// Events emitted during this tick
+    events: &'a mut Vec<SimEvent>,
+}
+
+impl<'a> TickContext<'a> {
+    pub fn new(
+        tick: Tick,
+        zone: ZoneId,
+        root: &'a dyn IRng,
+        events: &'a mut Vec<SimEvent>,
+    ) -> Self {
+        TickContext {
+            tick,
+            zone,
+            root,
+            system: "",
+            rng: zone_stream(root, "", zone, tick),
+            events,
+        }
+    }
+
+    /// Switch to the streams of `system` (called before each system runs).
+    pub fn begin_system(&mut self, system: SystemId) {
+        self.system = system;
+        self.rng = zone_stream(self.root, system, self.zone, self.tick);
+    }
+
+    /// System currently running.
+    pub fn system(&self) -> SystemId {
+        self.system
+    }
+
+    /// Zone being processed.
+    pub fn zone(&self) -> ZoneId {
+        self.zone
+    }
+
+    /// Stream of the current system for this zone and tick.
+    pub fn rng(&mut self) -> &mut dyn IRng {
+        self.rng.as_mut()
+    }
+
+    /// Stream of the current system for `entity_id` at this tick.
+    /// Independent of every other entity and system.
+    pub fn entity_rng(&self, entity_id: EntityId) -> Box<dyn IRng> {
+        self.root.split(stream_key(&[
+            ENTITY_STREAM,
+            name_key(self.system),
+            entity_id.as_u64(),
+            self.tick.as_u64(),
+        ]))
+    }
+
+    /// Emit an event at the current tick.
+    pub fn emit(&mut self, data: EventData) {
+        self.events.push(SimEvent::new(self.tick, data));
+    }
+
+    /// Events emitted so far in this zone during this tick. Their entities
+    /// are only touched (`Entity::last_changed`) once the tick is merged.
+    pub fn events(&self) -> &[SimEvent] {
+        self.events
+    }
+}
+
+fn zone_stream(root: &dyn IRng, system: SystemId, zone: ZoneId, tick: Tick) -> Box<dyn IRng> {
+    root.split(stream_key(&[
+        ZONE_STREAM,
+        name_key(system),
+        zone.as_u32() as u64,
+        tick.as_u64(),
+    ]))
+}
+
+/// A systemic rule executed once per tick, on every loaded zone.
+pub trait ITickSystem: Send + Sync {
+    /// Stable, unique identifier (used for ordering ties and replacement).
+    fn id(&self) -> SystemId;
+
+    /// Ordering key. Lower values run first.
+    fn order(&self) -> i32;
+
+    /// Run the system on one zone shard for the current tick.
+    fn run(&self, world: &mut World, ctx: &mut TickContext<'_>);
+
+    /// Advance one unloaded zone shard by `ticks` ticks in a single step
+    /// (coarse level of detail). The default has no coarse model: the
+    /// rule does not apply while the zone is unloaded.
+    fn run_coarse(&self, _world: &mut World, _ctx: &mut TickContext<'_>, _ticks: u64) {}
+}
+
+/// Ordering keys of the built-in systems.
+pub mod order {
+    pub const NEIGHBOUR_WAKE: i32 = 10;
+    pub const MOVEMENT: i32 = 50;
+    pub const RESOURCE_DEPLETION: i32 = 100;
+    pub const CREATURE_DEGRADATION: i32 = 200;
+    pub const ENTITY_EXPIRY: i32 = 8_000;
+    pub const IDLE_DORMANCY: i32 = 9_000;
+    pub const DEAD_ENTITY_CLEANUP: i32 = 10_000;
+}
+
+/// The built-in Phase 1 rules, in registration order.
+pub fn default_systems() -> Vec<Box<dyn ITickSystem>> {
+    vec![
+        Box::new(MovementSystem),
+        Box::new(ResourceDepletionSystem),
+        Box::new(CreatureDegradationSystem),
+        Box::new(EntityExpirySystem),
+        Box::new(DeadEntityCleanupSystem::default()),
+    ]
+}
+
+/// Sort systems into their deterministic execution order.
+pub fn sort_systems(systems: &mut [Box<dyn ITickSystem>]) {
+    systems.sort_by(|a, b| a.order().cmp(&b.order()).then_with(|| a.id().cmp(b.id())));
+}
+
+/// Collect IDs of simulated entities of the given kind (sorted by EntityId).
+pub fn simulated_entity_ids(world: &World, kind: EntityKind) -> Vec<EntityId> {
+    world
+        .simulated_entities()
+        .filter(|e| e.kind == kind)
+        .map(|e| e.id)
+        .collect()
+}
+
+/// Collect IDs of active entities of the given kind, loaded or not
+/// (sorted by EntityId). Used by coarse steps on unloaded zones.
+pub fn active_entity_ids(world: &World, kind: EntityKind) -> Vec<EntityId> {
+    world
+        .active_entities()
+        .filter(|e| e.kind == kind)
+        .map(|e| e.id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Named(SystemId, i32);
+
+    impl ITickSystem for Named {
+        fn id(&self) -> SystemId {
+            self.0
+        }
+
+        fn order(&self) -> i32 {
+            self.1
+        }
+
+        fn run(&self, _world: &mut World, _ctx: &mut TickContext<'_>) {}
+    }
+
+    #[test]
+    fn systems_sorted_by_order_then_id() {
+        let mut systems: Vec<Box<dyn ITickSystem>> = vec![
+            Box::new(Named("b", 10)),
+            Box::new(Named("z", 5)),
+            Box::new(Named("a", 10)),
+        ];
+        sort_systems(&mut systems);
+
+        let ids: Vec<SystemId> = systems.iter().map(|s| s.id()).collect();
+        assert_eq!(ids, vec!["z", "a", "b"]);
+    }
+
+    #[test]
+    fn default_systems_have_unique_ids() {
+        let systems = default_systems();
+        let mut ids: Vec<SystemId> = systems.iter().map(|s| s.id()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), systems.len());
+    }
+}
@@ -0,0 +1,120 @@
+//! # Movement
+//!
+//! Moves simulated entities that carry a `Velocity` or a `MoveTarget`.
+//! Movement stays within the entity's zone, except when an entity steps
+//! onto or across a portal entrance: it is then transferred to the portal
+//! destination (a single `EntityMoved` event with `from` and `to` in
+//! different zones), wherever the rest of the step would have led.
+//! Attached entities do not move on their own: they follow their parent.
+
+use sy_api::components::{Components, MoveTarget, Velocity};
+use sy_api::events::EventData;
+use sy_types::{EntityId, Position, WorldPos};
+
+use super::{order, ITickSystem, SystemId, TickContext};
+use crate::world::World;
+
+/// Applies velocities and walks entities towards their targets.
+pub struct MovementSystem;
+
+impl MovementSystem {
+    pub const ID: SystemId = "movement";
+}
+
+impl ITickSystem for MovementSystem {
+    fn id(&self) -> SystemId {
+        Self::ID
+    }
+
+    fn order(&self) -> i32 {
+        order::MOVEMENT
+    }
+
+    fn run(&self, world: &mut World, ctx: &mut TickContext<'_>) {
+        let movers: Vec<(EntityId, WorldPos)> = world
+            .simulated_entities()
+            .filter(|e| e.attachment.is_none())
+            .filter_map(|e| {
+                next_position(e.position.pos, &e.components).map(|pos| {
+                    (
+                        e.id,
+                        world.resolve_step(e.position.zone, e.position.pos, pos),
+                    )
+                })
+            })
+            .collect();
+
+        for (entity_id, to) in movers {
+            if let Some(from) = world.move_entity(entity_id, to) {
+                ctx.emit(EventData::EntityMoved {
+                    entity_id,
+                    from,
+                    to,
+                });
+            }
+        }
+    }
+}
+
+/// Compute the next local position, or None if the entity does not move.
+/// A `MoveTarget` takes precedence over a `Velocity`.
+fn next_position(pos: Position, components: &Components) -> Option<Position> {
+    let next = if let Some(target) = components.get::<MoveTarget>() {
+        Position::new(
+            step_towards(pos.x, target.target.x, target.speed),
+            step_towards(pos.y, target.target.y, target.speed),
+            step_towards(pos.z, target.target.z, target.speed),
+        )
+    } else if let Some(v) = components.get::<Velocity>() {
+        Position::new(
+            pos.x.saturating_add(v.dx),
+            pos.y.saturating_add(v.dy),
+            pos.z.saturating_add(v.dz),
+        )
+    } else {
+        return None;
+    };
+
+    (next != pos).then_some(next)
+}
+
+/// Move `from` towards `to` by at most `speed` units.
+fn step_towards(from: i32, to: i32, speed: u32) -> i32 {
+    let delta = (to as i64 - from as i64).clamp(-(speed as i64), speed as i64);
+    (from as i64 + delta) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_towards_clamps_to_speed() {
+        assert_eq!(step_towards(0, 10, 3), 3);
+        assert_eq!(step_towards(9, 10, 3), 10);
+        assert_eq!(step_towards(0, -10, 4), -4);
+        assert_eq!(step_towards(5, 5, 4), 5);
+    }
+
+    #[test]
+    fn target_takes_precedence_over_velocity() {
+        let components = Components::new()
+            .with(&Velocity {
+                dx: 1,
+                dy: 0,
+                dz: 0,
+            })
+            .unwrap()
+            .with(&MoveTarget {
+                target: Position::new(0, 5, 0),
+                speed: 2,
+            })
+            .unwrap();
+
+        assert_eq!(
+            next_position(Position::ORIGIN, &components),
+            Some(Position::new(0, 2, 0))
+        );
+        assert_eq!(next_position(Position::new(0, 5, 0), &components), None);
+    }
+}
@@ -0,0 +1,96 @@
+//! # Resource Depletion
+//!
+//! Resources lose one unit with a small probability each tick.
+//! A resource reaching zero is marked Dead.
+//! Coarse model (unloaded zones): the expected losses over the period.
+
+use sy_api::events::EventData;
+use sy_types::{EntityId, EntityKind, EntityState, Probability};
+
+use super::{active_entity_ids, order, simulated_entity_ids, ITickSystem, SystemId, TickContext};
+use crate::world::World;
+
+/// Resources degrade over time (simple rule).
+pub struct ResourceDepletionSystem;
+
+impl ResourceDepletionSystem {
+    pub const ID: SystemId = "resource_depletion";
+    /// Chance per tick to lose one unit
+    pub const CHANCE: Probability = Probability::new(1, 100);
+
+    /// Remove up to `losses` units from a resource, marking it Dead at zero.
+    fn deplete(world: &mut World, ctx: &mut TickContext<'_>, entity_id: EntityId, losses: u64) {
+        let entity = match world.entities.get_mut(&entity_id) {
+            Some(e) => e,
+            None => return,
+        };
+
+        let amt = entity.properties.amount.unwrap_or(0);
+        let amount = losses.min(amt as u64) as u32;
+        if amount == 0 {
+            return;
+        }
+
+        let new_amount = amt - amount;
+        entity.properties.amount = Some(new_amount);
+
+        ctx.emit(EventData::ResourceDepleted {
+            entity_id,
+            amount,
+            remaining: new_amount,
+        });
+
+        // If depleted, mark as dead
+        if new_amount == 0 {
+            let old_state = world.mark_dead(entity_id).unwrap_or(EntityState::Dead);
+
+            ctx.emit(EventData::EntityStateChanged {
+                entity_id,
+                old_state,
+                new_state: EntityState::Dead,
+            });
+        }
+    }
+
+    fn has_amount(world: &World, entity_id: EntityId) -> bool {
+        world
+            .entities
+            .get(&entity_id)
+            .is_some_and(|e| e.properties.amount.unwrap_or(0) > 0)
+    }
+}
+
+impl ITickSystem for ResourceDepletionSystem {
+    fn id(&self) -> SystemId {
+        Self::ID
+    }
+
+    fn order(&self) -> i32 {
+        order::RESOURCE_DEPLETION
+    }
+
+    fn run(&self, world: &mut World, ctx: &mut TickContext<'_>) {
+        for entity_id in simulated_entity_ids(world, EntityKind::Resource) {
+            if !Self::has_amount(world, entity_id)
+                || !ctx.entity_rng(entity_id).chance(Self::CHANCE)
+            {
+                continue;
+            }
+
+            Self::deplete(world, ctx, entity_id, 1);
+        }
+    }
+
+    fn run_coarse(&self, world: &mut World, ctx: &mut TickContext<'_>, ticks: u64) {
+        for entity_id in active_entity_ids(world, EntityKind::Resource) {
+            if !Self::has_amount(world, entity_id) {
+                continue;
+            }
+
+            let losses = ctx
+                .entity_rng(entity_id)
+                .expected_successes(ticks, Self::CHANCE);
+            Self::deplete(world, ctx, entity_id, losses);
+        }
+    }
+}
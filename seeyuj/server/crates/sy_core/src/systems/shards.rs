@@ -0,0 +1,81 @@
+//! # Zone Shards
+//!
+//! Runs the tick systems on detached zone shards, sequentially or on a
+//! pool of scoped threads. Shards never share state, so the outcome of
+//! each shard is independent of the thread it ran on.
+
+use sy_api::events::SimEvent;
+use sy_types::{Tick, ZoneId};
+
+use super::{ITickSystem, TickContext};
+use crate::ports::IRng;
+use crate::world::World;
+
+/// Stream of the per-shard root generators (same for every shard).
+const ROOT_STREAM: u64 = 0;
+
+/// One loaded zone detached for a tick, with its own root generator split
+/// off the world root (streams are split from it, it is never advanced).
+pub(crate) struct ZoneShard {
+    pub zone_id: ZoneId,
+    pub world: World,
+    pub root: Box<dyn IRng>,
+    pub events: Vec<SimEvent>,
+}
+
+impl ZoneShard {
+    pub fn new(zone_id: ZoneId, world: World, world_root: &dyn IRng) -> Self {
+        ZoneShard {
+            zone_id,
+            world,
+            root: world_root.split(ROOT_STREAM),
+            events: Vec::new(),
+        }
+    }
+
+    fn run(&mut self, systems: &[Box<dyn ITickSystem>], tick: Tick) {
+        let mut ctx = TickContext::new(tick, self.zone_id, self.root.as_ref(), &mut self.events);
+        for system in systems {
+            ctx.begin_system(system.id());
+            system.run(&mut self.world, &mut ctx);
+        }
+    }
+
+    /// Advance the (unloaded) zone by `ticks` ticks up to `tick` with the
+    /// coarse models of the systems.
+    pub fn run_coarse(&mut self, systems: &[Box<dyn ITickSystem>], tick: Tick, ticks: u64) {
+        let mut ctx = TickContext::new(tick, self.zone_id, self.root.as_ref(), &mut self.events);
+        for system in systems {
+            ctx.begin_system(system.id());
+            system.run_coarse(&mut self.world, &mut ctx, ticks);
+        }
+    }
+}
+
+/// Run all systems on every shard using up to `threads` threads.
+/// Shards are split into contiguous chunks; their order is preserved.
+pub(crate) fn run_zone_shards(
+    systems: &[Box<dyn ITickSystem>],
+    shards: &mut [ZoneShard],
+    tick: Tick,
+    threads: usize,
+) {
+    let threads = threads.clamp(1, shards.len().max(1));
+    if threads == 1 {
+        for shard in shards.iter_mut() {
+            shard.run(systems, tick);
+        }
+        return;
+    }
+
+    let chunk = shards.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        for part in shards.chunks_mut(chunk) {
+            scope.spawn(move || {
+                for shard in part {
+                    shard.run(systems, tick);
+                }
+            });
+        }
+    });
+}
@@ -0,0 +1,199 @@
+//! # Timers
+//!
+//! Persistent queue of commands scheduled at future ticks.
+//!
+//! ## Design
+//! - Timers live in `World` (serialized with the snapshot, hashed)
+//! - Firing order is (fire_at, timer_id): stable across runs
+//! - Every change is mirrored by an event (TimerScheduled / TimerFired /
+//!   TimerCancelled) so replay rebuilds the exact same queue
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use sy_api::commands::Command;
+use sy_types::{Tick, TimerId};
+
+/// A command scheduled at a future tick.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timer {
+    /// Timer identifier (allocated by the queue)
+    pub id: TimerId,
+    /// Next tick at which the timer fires
+    pub fire_at: Tick,
+    /// Repeat period in ticks (None = one-shot)
+    pub repeat_every: Option<u64>,
+    /// The command to run
+    pub command: Command,
+}
+
+impl Timer {
+    /// Tick of the firing after one at `fired_at` (None for one-shot timers).
+    pub fn next_fire(&self, fired_at: Tick) -> Option<Tick> {
+        self.repeat_every
+            .map(|every| Tick(fired_at.as_u64().saturating_add(every)))
+    }
+}
+
+/// Timer queue stored in the world.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimerQueue {
+    /// Next timer ID to assign
+    next_id: u64,
+    /// All timers, indexed by ID
+    timers: BTreeMap<TimerId, Timer>,
+    /// Firing schedule: tick -> timer IDs (sorted)
+    schedule: BTreeMap<Tick, Vec<TimerId>>,
+}
+
+impl Default for TimerQueue {
+    fn default() -> Self {
+        TimerQueue {
+            next_id: 1,
+            timers: BTreeMap::new(),
+            schedule: BTreeMap::new(),
+        }
+    }
+}
+
+impl TimerQueue {
+    /// Allocate a new timer ID.
+    pub fn allocate_id(&mut self) -> TimerId {
+        let id = TimerId::new(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    /// Next timer ID that will be assigned.
+    pub fn next_id(&self) -> u64 {
+        self.next_id
+    }
+
+    /// Insert a timer (replaces any timer with the same ID).
+    pub fn insert(&mut self, timer: Timer) {
+        if timer.id.as_u64() >= self.next_id {
+            self.next_id = timer.id.as_u64() + 1;
+        }
+        self.remove(timer.id);
+        self.add_to_schedule(timer.fire_at, timer.id);
+        self.timers.insert(timer.id, timer);
+    }
+
+    /// Remove a timer. Returns it if it existed.
+    pub fn remove(&mut self, id: TimerId) -> Option<Timer> {
+        let timer = self.timers.remove(&id)?;
+        self.remove_from_schedule(timer.fire_at, id);
+        Some(timer)
+    }
+
+    /// Get a timer by ID.
+    pub fn get(&self, id: TimerId) -> Option<&Timer> {
+        self.timers.get(&id)
+    }
+
+    /// IDs of timers due at or before `tick`, in firing order.
+    pub fn due(&self, tick: Tick) -> Vec<TimerId> {
+        self.schedule
+            .range(..=tick)
+            .flat_map(|(_, ids)| ids.iter().copied())
+            .collect()
+    }
+
+    /// Complete a firing: reschedule at `next_fire`, or remove the timer.
+    pub fn complete(&mut self, id: TimerId, next_fire: Option<Tick>) {
+        match next_fire {
+            Some(at) => {
+                if let Some(mut timer) = self.remove(id) {
+                    timer.fire_at = at;
+                    self.insert(timer);
+                }
+            }
+            None => {
+                self.remove(id);
+            }
+        }
+    }
+
+    /// Iterate timers in ID order.
+    pub fn iter(&self) -> impl Iterator<Item = &Timer> {
+        self.timers.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    fn add_to_schedule(&mut self, at: Tick, id: TimerId) {
+        let ids = self.schedule.entry(at).or_default();
+        if let Err(pos) = ids.binary_search(&id) {
+            ids.insert(pos, id);
+        }
+    }
+
+    fn remove_from_schedule(&mut self, at: Tick, id: TimerId) {
+        if let Some(ids) = self.schedule.get_mut(&at) {
+            ids.retain(|&t| t != id);
+            if ids.is_empty() {
+                self.schedule.remove(&at);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timer(queue: &mut TimerQueue, at: u64, repeat_every: Option<u64>) -> TimerId {
+        let id = queue.allocate_id();
+        queue.insert(Timer {
+            id,
+            fire_at: Tick(at),
+            repeat_every,
+            command: Command::SaveWorld,
+        });
+        id
+    }
+
+    #[test]
+    fn due_in_fire_order() {
+        let mut queue = TimerQueue::default();
+        let late = timer(&mut queue, 20, None);
+        let a = timer(&mut queue, 10, None);
+        let b = timer(&mut queue, 10, None);
+
+        assert!(queue.due(Tick(9)).is_empty());
+        assert_eq!(queue.due(Tick(10)), vec![a, b]);
+        assert_eq!(queue.due(Tick(25)), vec![a, b, late]);
+    }
+
+    #[test]
+    fn complete_reschedules_or_removes() {
+        let mut queue = TimerQueue::default();
+        let repeating = timer(&mut queue, 5, Some(5));
+        let once = timer(&mut queue, 5, None);
+
+        let next = queue.get(repeating).unwrap().next_fire(Tick(5));
+        queue.complete(repeating, next);
+        queue.complete(once, None);
+
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue.get(repeating).unwrap().fire_at, Tick(10));
+        assert!(queue.due(Tick(9)).is_empty());
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        let mut queue = TimerQueue::default();
+        timer(&mut queue, 3, None);
+
+        let json = serde_json::to_string(&queue).unwrap();
+        let restored: TimerQueue = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.due(Tick(3)).len(), 1);
+        assert_eq!(restored.next_id(), 2);
+    }
+}
@@ -13,11 +13,19 @@
 //! This is critical for reproducible hash computation and simulation.
 
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
+use sy_api::archetypes::Archetype;
 use sy_api::commands::EntityProperties;
+use sy_api::components::{Component, Components};
+use sy_api::events::{DespawnReason, EventData};
+
+use crate::inputs::InputQueue;
+use crate::spatial::SpatialIndex;
+use crate::timers::TimerQueue;
 use sy_types::{
-    EntityId, EntityKind, EntityState, EventId, RngSeed, SimTime, Tick, WorldMeta, WorldPos, ZoneId,
+    Attachment, EntityId, EntityKind, EntityState, EventId, OnParentDespawn, Portal, Position,
+    RngSeed, SimTime, Tick, WorldMeta, WorldPos, ZoneId,
 };
 
 // ============================================================================
@@ -37,8 +45,24 @@ pub struct Entity {
     pub position: WorldPos,
     /// Tick when created
     pub created_at: Tick,
+    /// Tick of the last event about this entity (drives dormancy rules)
+    #[serde(default)]
+    pub last_changed: Tick,
+    /// Tick of the last event about this entity other than falling asleep
+    /// or waking up (drives neighbour wake)
+    #[serde(default)]
+    pub last_active: Tick,
     /// Properties
     pub properties: EntityProperties,
+    /// Typed components (ordered by name)
+    #[serde(default)]
+    pub components: Components,
+    /// Tick at which the entity expires (None = never)
+    #[serde(default)]
+    pub expires_at: Option<Tick>,
+    /// Parent holding this entity (None = loose in the world)
+    #[serde(default)]
+    pub attachment: Option<Attachment>,
 }
 
 impl Entity {
@@ -55,10 +79,31 @@ impl Entity {
             state: EntityState::Active,
             position,
             created_at,
+            last_changed: created_at,
+            last_active: created_at,
             properties,
+            components: Components::new(),
+            expires_at: None,
+            attachment: None,
         }
     }
 
+    /// Attach initial components (builder-style).
+    pub fn with_components(mut self, components: Components) -> Self {
+        self.components = components;
+        self
+    }
+
+    /// Get a typed component.
+    pub fn component<T: Component>(&self) -> Option<T> {
+        self.components.get::<T>()
+    }
+
+    /// Attach (or replace) a typed component.
+    pub fn set_component<T: Component>(&mut self, component: &T) -> Result<(), String> {
+        self.components.insert(component)
+    }
+
     pub fn is_active(&self) -> bool {
         self.state == EntityState::Active
     }
@@ -66,6 +111,15 @@ impl Entity {
     pub fn is_dead(&self) -> bool {
         self.state == EntityState::Dead
     }
+
+    pub fn is_dormant(&self) -> bool {
+        self.state == EntityState::Dormant
+    }
+
+    /// Entity holding this one, if attached.
+    pub fn parent(&self) -> Option<EntityId> {
+        self.attachment.map(|a| a.parent)
+    }
 }
 
 // ============================================================================
@@ -82,8 +136,18 @@ pub struct Zone {
     pub name: Option<String>,
     /// Whether this zone is currently loaded/active
     pub loaded: bool,
+    /// Tick up to which the zone was simulated while unloaded (coarse
+    /// model, see `Simulation::set_coarse_interval`); unused while loaded
+    #[serde(default)]
+    pub simulated_until: Tick,
     /// Entity IDs in this zone
     pub entities: Vec<EntityId>,
+    /// Neighbouring zones (symmetric)
+    #[serde(default)]
+    pub neighbours: BTreeSet<ZoneId>,
+    /// Portals leading out of this zone, sorted by entrance position
+    #[serde(default)]
+    pub portals: Vec<Portal>,
 }
 
 impl Zone {
@@ -92,7 +156,10 @@ impl Zone {
             id,
             name,
             loaded: true,
+            simulated_until: Tick::ZERO,
             entities: Vec::new(),
+            neighbours: BTreeSet::new(),
+            portals: Vec::new(),
         }
     }
 
@@ -105,6 +172,33 @@ impl Zone {
     pub fn remove_entity(&mut self, id: EntityId) {
         self.entities.retain(|&e| e != id);
     }
+
+    /// Portal whose entrance is at `pos`, if any.
+    pub fn portal_at(&self, pos: Position) -> Option<&Portal> {
+        self.portals
+            .binary_search_by(|p| p.from.cmp(&pos))
+            .ok()
+            .map(|i| &self.portals[i])
+    }
+
+    /// Add or replace the portal at `portal.from`. Returns the replaced one.
+    pub fn set_portal(&mut self, portal: Portal) -> Option<Portal> {
+        match self.portals.binary_search_by(|p| p.from.cmp(&portal.from)) {
+            Ok(i) => Some(std::mem::replace(&mut self.portals[i], portal)),
+            Err(i) => {
+                self.portals.insert(i, portal);
+                None
+            }
+        }
+    }
+
+    /// Remove the portal at `from`. Returns it if it existed.
+    pub fn remove_portal(&mut self, from: Position) -> Option<Portal> {
+        self.portals
+            .binary_search_by(|p| p.from.cmp(&from))
+            .ok()
+            .map(|i| self.portals.remove(i))
+    }
 }
 
 // ============================================================================
@@ -125,7 +219,8 @@ pub struct World {
     pub current_tick: Tick,
     /// Current simulated time
     pub sim_time: SimTime,
-    /// RNG state (for determinism)
+    /// Root RNG state: every stream used by tick systems is split off it
+    /// by (system, entity, tick), so checkpointing it checkpoints them all
     pub rng_state: u64,
     /// Next entity ID to assign
     pub next_entity_id: u64,
@@ -133,6 +228,24 @@ pub struct World {
     pub entities: BTreeMap<EntityId, Entity>,
     /// All zones, indexed by ID (BTreeMap for deterministic order)
     pub zones: BTreeMap<ZoneId, Zone>,
+    /// Commands scheduled at future ticks
+    #[serde(default)]
+    pub timers: TimerQueue,
+    /// External inputs waiting for their tick
+    #[serde(default)]
+    pub inputs: InputQueue,
+    /// Archetype registry, by name (a copy of the templates this world uses)
+    #[serde(default)]
+    pub archetypes: BTreeMap<String, Archetype>,
+    /// Expiry schedule: tick -> entity IDs (sorted), mirrors `Entity::expires_at`
+    #[serde(default)]
+    expiries: BTreeMap<Tick, Vec<EntityId>>,
+    /// Per-zone spatial index (derived from entity positions, not serialized)
+    #[serde(skip)]
+    spatial: BTreeMap<ZoneId, SpatialIndex>,
+    /// Parent -> attached entities (derived from `Entity::attachment`, not serialized)
+    #[serde(skip)]
+    children: BTreeMap<EntityId, BTreeSet<EntityId>>,
 }
 
 impl World {
@@ -153,6 +266,7 @@ impl World {
             snapshot_tick: Tick::ZERO,    // Will be updated on save
             last_event_id: EventId::ZERO, // No events yet
             format_version: WorldMeta::CURRENT_FORMAT_VERSION,
+            forked_from: None,
         };
 
         let mut world = World {
@@ -163,6 +277,12 @@ impl World {
             next_entity_id: 1, // 0 is reserved for INVALID
             entities: BTreeMap::new(),
             zones: BTreeMap::new(),
+            timers: TimerQueue::default(),
+            inputs: InputQueue::default(),
+            archetypes: BTreeMap::new(),
+            expiries: BTreeMap::new(),
+            spatial: BTreeMap::new(),
+            children: BTreeMap::new(),
         };
 
         // Create the origin zone by default
@@ -204,8 +324,29 @@ impl World {
     pub fn add_entity(&mut self, entity: Entity) {
         let zone_id = entity.position.zone;
         let entity_id = entity.id;
+        let expires_at = entity.expires_at;
+        let parent = entity.parent();
+        let pos = entity.position.pos;
 
-        self.entities.insert(entity_id, entity);
+        if let Some(old) = self.entities.insert(entity_id, entity) {
+            if let Some(at) = old.expires_at {
+                self.unschedule_expiry(at, entity_id);
+            }
+            if let Some(old_parent) = old.parent() {
+                self.unlink_child(old_parent, entity_id);
+            }
+            self.unindex(old.position.zone, entity_id);
+        }
+        if let Some(parent) = parent {
+            self.children.entry(parent).or_default().insert(entity_id);
+        }
+        self.spatial
+            .entry(zone_id)
+            .or_default()
+            .insert(entity_id, pos);
+        if let Some(at) = expires_at {
+            self.schedule_expiry(at, entity_id);
+        }
 
         // Add to zone
         if let Some(zone) = self.zones.get_mut(&zone_id) {
@@ -214,18 +355,341 @@ impl World {
     }
 
     /// Remove an entity from the world.
+    /// Children still attached to it are left loose (see `despawn_entity`
+    /// for policy-driven removal).
     pub fn remove_entity(&mut self, id: EntityId) -> Option<Entity> {
         if let Some(entity) = self.entities.remove(&id) {
             // Remove from zone
             if let Some(zone) = self.zones.get_mut(&entity.position.zone) {
                 zone.remove_entity(id);
             }
+            if let Some(at) = entity.expires_at {
+                self.unschedule_expiry(at, id);
+            }
+            if let Some(parent) = entity.parent() {
+                self.unlink_child(parent, id);
+            }
+            for child in self.children.remove(&id).unwrap_or_default() {
+                if let Some(child) = self.entities.get_mut(&child) {
+                    child.attachment = None;
+                }
+            }
+            self.unindex(entity.position.zone, id);
             Some(entity)
         } else {
             None
         }
     }
 
+    /// Despawn an entity. Its children are despawned or detached according
+    /// to their `on_parent_despawn` policy, depth first in EntityId order.
+    /// Returns the resulting events (children first, the entity last), or
+    /// None if the entity does not exist.
+    pub fn despawn_entity(
+        &mut self,
+        id: EntityId,
+        reason: DespawnReason,
+    ) -> Option<Vec<EventData>> {
+        if !self.entities.contains_key(&id) {
+            return None;
+        }
+        let mut events = Vec::new();
+        self.despawn_tree(id, reason, &mut events);
+        Some(events)
+    }
+
+    fn despawn_tree(&mut self, id: EntityId, reason: DespawnReason, events: &mut Vec<EventData>) {
+        for child in self.children_of(id) {
+            let policy = self.entities[&child]
+                .attachment
+                .map(|a| a.on_parent_despawn)
+                .unwrap_or_default();
+            match policy {
+                OnParentDespawn::Cascade => {
+                    self.despawn_tree(child, DespawnReason::ParentDespawned, events)
+                }
+                OnParentDespawn::Orphan => {
+                    self.detach_entity(child);
+                    events.push(EventData::EntityDetached {
+                        entity_id: child,
+                        parent_id: id,
+                    });
+                }
+            }
+        }
+
+        self.remove_entity(id);
+        events.push(EventData::EntityDespawned {
+            entity_id: id,
+            reason,
+        });
+    }
+
+    /// Move an entity and everything attached to it, keeping zone membership
+    /// consistent. Returns the previous position, or None if the entity does
+    /// not exist.
+    pub fn move_entity(&mut self, id: EntityId, to: WorldPos) -> Option<WorldPos> {
+        let from = self.place_entity(id, to)?;
+        for child in self.descendants(id) {
+            self.place_entity(child, to);
+        }
+        Some(from)
+    }
+
+    fn place_entity(&mut self, id: EntityId, to: WorldPos) -> Option<WorldPos> {
+        let entity = self.entities.get_mut(&id)?;
+        let from = entity.position;
+        entity.position = to;
+
+        if from.zone != to.zone {
+            if let Some(old_zone) = self.zones.get_mut(&from.zone) {
+                old_zone.remove_entity(id);
+            }
+            if let Some(new_zone) = self.zones.get_mut(&to.zone) {
+                new_zone.add_entity(id);
+            }
+            self.unindex(from.zone, id);
+        }
+        self.spatial.entry(to.zone).or_default().insert(id, to.pos);
+
+        Some(from)
+    }
+
+    // ========================================================================
+    // Hierarchy
+    // ========================================================================
+
+    /// Entities attached directly to `id`, sorted by EntityId.
+    pub fn children_of(&self, id: EntityId) -> Vec<EntityId> {
+        self.children
+            .get(&id)
+            .map(|c| c.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Everything attached to `id`, directly or not: depth first, children
+    /// in EntityId order.
+    pub fn descendants(&self, id: EntityId) -> Vec<EntityId> {
+        let mut found = Vec::new();
+        let mut stack = self.children_of(id);
+        stack.reverse();
+        while let Some(child) = stack.pop() {
+            found.push(child);
+            stack.extend(self.children_of(child).into_iter().rev());
+        }
+        found
+    }
+
+    /// Attach an entity (with its own children) to a parent, moving them to
+    /// the parent's position. Returns the previous parent.
+    pub fn attach_entity(
+        &mut self,
+        id: EntityId,
+        attachment: Attachment,
+    ) -> Result<Option<EntityId>, String> {
+        let to = match self.entities.get(&attachment.parent) {
+            Some(parent) => parent.position,
+            None => return Err(format!("Parent not found: {}", attachment.parent)),
+        };
+        if attachment.parent == id || self.descendants(id).contains(&attachment.parent) {
+            return Err(format!(
+                "Attaching {} to {} would create a cycle",
+                id, attachment.parent
+            ));
+        }
+        let entity = self
+            .entities
+            .get_mut(&id)
+            .ok_or_else(|| format!("Entity not found: {}", id))?;
+
+        let old_parent = entity.attachment.replace(attachment).map(|a| a.parent);
+        if let Some(old) = old_parent {
+            self.unlink_child(old, id);
+        }
+        self.children
+            .entry(attachment.parent)
+            .or_default()
+            .insert(id);
+        self.move_entity(id, to);
+
+        Ok(old_parent)
+    }
+
+    /// Detach an entity from its parent; it stays where it is.
+    /// Returns the former parent, or None if the entity was not attached.
+    pub fn detach_entity(&mut self, id: EntityId) -> Option<EntityId> {
+        let parent = self.entities.get_mut(&id)?.attachment.take()?.parent;
+        self.unlink_child(parent, id);
+        Some(parent)
+    }
+
+    /// Rebuild the parent -> children index (after deserialization).
+    pub fn rebuild_hierarchy(&mut self) {
+        self.children.clear();
+        for entity in self.entities.values() {
+            if let Some(parent) = entity.parent() {
+                self.children.entry(parent).or_default().insert(entity.id);
+            }
+        }
+    }
+
+    /// The whole parent -> children index (for invariant checks).
+    pub(crate) fn hierarchy(&self) -> &BTreeMap<EntityId, BTreeSet<EntityId>> {
+        &self.children
+    }
+
+    fn unlink_child(&mut self, parent: EntityId, child: EntityId) {
+        if let Some(children) = self.children.get_mut(&parent) {
+            children.remove(&child);
+            if children.is_empty() {
+                self.children.remove(&parent);
+            }
+        }
+    }
+
+    /// Record that the entity of `event` changed at `tick`.
+    /// Called for every event, both live and on replay.
+    pub fn touch_entity(&mut self, event: &EventData, tick: Tick) {
+        let Some(entity) = event.entity_id().and_then(|id| self.entities.get_mut(&id)) else {
+            return;
+        };
+        entity.last_changed = entity.last_changed.max(tick);
+        if !event.is_dormancy_change() {
+            entity.last_active = entity.last_active.max(tick);
+        }
+    }
+
+    /// Set (or clear) the expiry tick of an entity, keeping the expiry
+    /// schedule consistent. Returns the previous value, or None if the
+    /// entity does not exist.
+    pub fn set_entity_expiry(
+        &mut self,
+        id: EntityId,
+        expires_at: Option<Tick>,
+    ) -> Option<Option<Tick>> {
+        let entity = self.entities.get_mut(&id)?;
+        let old = std::mem::replace(&mut entity.expires_at, expires_at);
+
+        if let Some(at) = old {
+            self.unschedule_expiry(at, id);
+        }
+        if let Some(at) = expires_at {
+            self.schedule_expiry(at, id);
+        }
+
+        Some(old)
+    }
+
+    /// Mark an entity Dead. A dead entity only waits for cleanup: it leaves
+    /// the expiry schedule, but keeps its position until despawned. Returns
+    /// its previous state, or None if the entity does not exist.
+    pub fn mark_dead(&mut self, id: EntityId) -> Option<EntityState> {
+        let entity = self.entities.get_mut(&id)?;
+        let old = std::mem::replace(&mut entity.state, EntityState::Dead);
+        self.set_entity_expiry(id, None);
+        Some(old)
+    }
+
+    /// IDs of entities expiring at or before `tick` (by tick, then EntityId).
+    /// Only touches the expiry schedule, never the full entity map.
+    pub fn expired_entities(&self, tick: Tick) -> Vec<EntityId> {
+        self.expiries
+            .range(..=tick)
+            .flat_map(|(_, ids)| ids.iter().copied())
+            .collect()
+    }
+
+    /// The whole expiry schedule (for invariant checks).
+    pub(crate) fn expiry_schedule(&self) -> &BTreeMap<Tick, Vec<EntityId>> {
+        &self.expiries
+    }
+
+    fn schedule_expiry(&mut self, at: Tick, id: EntityId) {
+        let ids = self.expiries.entry(at).or_default();
+        if let Err(pos) = ids.binary_search(&id) {
+            ids.insert(pos, id);
+        }
+    }
+
+    fn unschedule_expiry(&mut self, at: Tick, id: EntityId) {
+        if let Some(ids) = self.expiries.get_mut(&at) {
+            ids.retain(|&e| e != id);
+            if ids.is_empty() {
+                self.expiries.remove(&at);
+            }
+        }
+    }
+
+    // ========================================================================
+    // Spatial queries
+    // ========================================================================
+
+    /// Spatial index of a zone (None if no entity was ever indexed there).
+    pub fn spatial_index(&self, zone: ZoneId) -> Option<&SpatialIndex> {
+        self.spatial.get(&zone)
+    }
+
+    /// Entities of `zone` within Euclidean distance `radius` of `center`,
+    /// sorted by EntityId.
+    pub fn entities_within(&self, zone: ZoneId, center: Position, radius: u32) -> Vec<EntityId> {
+        self.spatial
+            .get(&zone)
+            .map(|index| index.within_radius(center, radius))
+            .unwrap_or_default()
+    }
+
+    /// Entities of `zone` inside the box [min, max] (inclusive), sorted by EntityId.
+    pub fn entities_in_box(&self, zone: ZoneId, min: Position, max: Position) -> Vec<EntityId> {
+        self.spatial
+            .get(&zone)
+            .map(|index| index.in_box(min, max))
+            .unwrap_or_default()
+    }
+
+    /// Up to `n` entities of `kind` in `zone`, closest to `center` first
+    /// (ties broken by EntityId).
+    pub fn nearest_of_kind(
+        &self,
+        zone: ZoneId,
+        center: Position,
+        kind: EntityKind,
+        n: usize,
+    ) -> Vec<EntityId> {
+        self.spatial
+            .get(&zone)
+            .map(|index| {
+                index.nearest(center, n, |id| {
+                    self.entities.get(&id).is_some_and(|e| e.kind == kind)
+                })
+            })
+            .unwrap_or_default()
+    }
+
+    /// Rebuild the spatial index from entity positions (after deserialization).
+    pub fn rebuild_spatial_index(&mut self) {
+        self.spatial.clear();
+        for entity in self.entities.values() {
+            self.spatial
+                .entry(entity.position.zone)
+                .or_default()
+                .insert(entity.id, entity.position.pos);
+        }
+    }
+
+    /// Every per-zone spatial index (for invariant checks).
+    pub(crate) fn spatial_indexes(&self) -> &BTreeMap<ZoneId, SpatialIndex> {
+        &self.spatial
+    }
+
+    fn unindex(&mut self, zone: ZoneId, id: EntityId) {
+        if let Some(index) = self.spatial.get_mut(&zone) {
+            index.remove(id);
+            if index.is_empty() {
+                self.spatial.remove(&zone);
+            }
+        }
+    }
+
     /// Get an entity by ID.
     pub fn get_entity(&self, id: EntityId) -> Option<&Entity> {
         self.entities.get(&id)
@@ -241,6 +705,13 @@ impl World {
         self.entities.values().filter(|e| e.is_active())
     }
 
+    /// Get active entities in loaded zones (the ones tick systems process).
+    pub fn simulated_entities(&self) -> impl Iterator<Item = &Entity> {
+        self.entities
+            .values()
+            .filter(|e| e.is_active() && self.is_zone_loaded(e.position.zone))
+    }
+
     /// Count entities by state.
     pub fn entity_count(&self) -> usize {
         self.entities.len()
@@ -274,6 +745,79 @@ impl World {
         self.zones.contains_key(&id)
     }
 
+    /// Check if a zone exists and is loaded.
+    pub fn is_zone_loaded(&self, id: ZoneId) -> bool {
+        self.zones.get(&id).map(|z| z.loaded).unwrap_or(false)
+    }
+
+    /// Link two zones as neighbours. Returns false if either zone is
+    /// missing or they were already linked.
+    pub fn link_zones(&mut self, a: ZoneId, b: ZoneId) -> bool {
+        if a == b || !self.has_zone(a) || !self.has_zone(b) {
+            return false;
+        }
+        let added = self
+            .zones
+            .get_mut(&a)
+            .is_some_and(|z| z.neighbours.insert(b));
+        self.zones
+            .get_mut(&b)
+            .is_some_and(|z| z.neighbours.insert(a))
+            || added
+    }
+
+    /// Unlink two zones and drop the portals between them.
+    /// Returns false if they were not linked.
+    pub fn unlink_zones(&mut self, a: ZoneId, b: ZoneId) -> bool {
+        let mut removed = false;
+        for (from, to) in [(a, b), (b, a)] {
+            if let Some(zone) = self.zones.get_mut(&from) {
+                removed |= zone.neighbours.remove(&to);
+                zone.portals.retain(|p| p.to.zone != to);
+            }
+        }
+        removed
+    }
+
+    /// Add (or replace) a portal, linking its two zones.
+    /// Returns false if either zone is missing.
+    pub fn add_portal(&mut self, zone_id: ZoneId, portal: Portal) -> bool {
+        if zone_id == portal.to.zone || !self.has_zone(portal.to.zone) {
+            return false;
+        }
+        let Some(zone) = self.zones.get_mut(&zone_id) else {
+            return false;
+        };
+        zone.set_portal(portal);
+        self.link_zones(zone_id, portal.to.zone);
+        true
+    }
+
+    /// Where an entity arriving at `pos` ends up: the portal destination if
+    /// `pos` is a portal entrance, otherwise `pos` itself (portals never chain).
+    pub fn resolve_portal(&self, pos: WorldPos) -> WorldPos {
+        self.zones
+            .get(&pos.zone)
+            .and_then(|z| z.portal_at(pos.pos))
+            .map_or(pos, |p| p.to)
+    }
+
+    /// Where an entity stepping from `from` to `to` in `zone` ends up: the
+    /// destination of the first portal entrance it crosses (the landing cell
+    /// included, the starting cell not), otherwise `to`. See `step_index`
+    /// for the cells crossed.
+    pub fn resolve_step(&self, zone: ZoneId, from: Position, to: Position) -> WorldPos {
+        self.zones
+            .get(&zone)
+            .and_then(|z| {
+                z.portals
+                    .iter()
+                    .filter_map(|p| step_index(from, to, p.from).map(|k| (k, p)))
+                    .min_by_key(|(k, _)| *k)
+            })
+            .map_or(WorldPos::new(zone, to), |(_, p)| p.to)
+    }
+
     /// Get all zone IDs.
     pub fn zone_ids(&self) -> impl Iterator<Item = ZoneId> + '_ {
         self.zones.keys().copied()
@@ -284,6 +828,92 @@ impl World {
         self.zones.len()
     }
 
+    // ========================================================================
+    // Zone shards (parallel tick)
+    // ========================================================================
+
+    /// Detach a zone and its entities into a standalone shard world.
+    /// The shard shares metadata and time with this world but holds no
+    /// timers, no inputs, no archetypes and no other zone. Returns None if the zone does not exist.
+    pub(crate) fn take_zone_shard(&mut self, zone_id: ZoneId) -> Option<World> {
+        let zone = self.zones.remove(&zone_id)?;
+
+        let mut shard = World {
+            meta: self.meta.clone(),
+            current_tick: self.current_tick,
+            sim_time: self.sim_time,
+            rng_state: self.rng_state,
+            next_entity_id: self.next_entity_id,
+            entities: BTreeMap::new(),
+            zones: BTreeMap::new(),
+            timers: TimerQueue::default(),
+            inputs: InputQueue::default(),
+            archetypes: BTreeMap::new(),
+            expiries: BTreeMap::new(),
+            spatial: BTreeMap::new(),
+            children: BTreeMap::new(),
+        };
+
+        for &id in &zone.entities {
+            if let Some(entity) = self.entities.remove(&id) {
+                if let Some(at) = entity.expires_at {
+                    self.unschedule_expiry(at, id);
+                    shard.schedule_expiry(at, id);
+                }
+                // Attached entities always share their parent's zone
+                if let Some(children) = self.children.remove(&id) {
+                    shard.children.insert(id, children);
+                }
+                shard.entities.insert(id, entity);
+            }
+        }
+        if let Some(index) = self.spatial.remove(&zone_id) {
+            shard.spatial.insert(zone_id, index);
+        }
+        shard.zones.insert(zone_id, zone);
+
+        Some(shard)
+    }
+
+    /// Merge shards produced by `take_zone_shard` back, in the given order.
+    /// All zones are restored before entities are re-added, so entities that
+    /// crossed a portal into another shard's zone join it in shard order.
+    pub(crate) fn merge_zone_shards(&mut self, shards: Vec<World>) {
+        let mut transferred = Vec::new();
+        for mut shard in shards {
+            debug_assert_eq!(
+                shard.next_entity_id, self.next_entity_id,
+                "tick systems must not allocate entity IDs"
+            );
+            let own_zones: BTreeSet<ZoneId> = shard.zones.keys().copied().collect();
+            for (zone_id, zone) in std::mem::take(&mut shard.zones) {
+                if let Some(index) = shard.spatial.remove(&zone_id) {
+                    self.spatial.insert(zone_id, index);
+                }
+                self.zones.insert(zone_id, zone);
+            }
+
+            // Entities still in their zone are already listed and indexed there
+            for (id, entity) in std::mem::take(&mut shard.entities) {
+                if own_zones.contains(&entity.position.zone) {
+                    if let Some(at) = entity.expires_at {
+                        self.schedule_expiry(at, id);
+                    }
+                    if let Some(children) = shard.children.remove(&id) {
+                        self.children.insert(id, children);
+                    }
+                    self.entities.insert(id, entity);
+                } else {
+                    transferred.push(entity);
+                }
+            }
+        }
+
+        for entity in transferred {
+            self.add_entity(entity);
+        }
+    }
+
     // ========================================================================
     // Time
     // ========================================================================
@@ -305,9 +935,12 @@ impl World {
         bincode_serialize(self).map_err(|e| e.to_string())
     }
 
-    /// Deserialize from bytes.
+    /// Deserialize from bytes (rebuilds derived indexes).
     pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
-        bincode_deserialize(data).map_err(|e| e.to_string())
+        let mut world: World = bincode_deserialize(data).map_err(|e| e.to_string())?;
+        world.rebuild_spatial_index();
+        world.rebuild_hierarchy();
+        Ok(world)
     }
 }
 
@@ -325,6 +958,34 @@ fn bincode_deserialize<'a, T: Deserialize<'a>>(
     serde_json::from_slice(data).map_err(|_| serde::de::Error::custom("deserialization failed"))
 }
 
+/// Position of `cell` along the step from `from` to `to` (1 to n, where n
+/// is the largest per-axis distance), or None if the step does not cross it.
+/// Cell k of the step is `from + delta * k / n` on every axis (rounded
+/// towards `from`), so a step crosses n cells, one unit at a time along its
+/// longest axis.
+fn step_index(from: Position, to: Position, cell: Position) -> Option<i64> {
+    let axes = [
+        (from.x as i64, to.x as i64 - from.x as i64, cell.x as i64),
+        (from.y as i64, to.y as i64 - from.y as i64, cell.y as i64),
+        (from.z as i64, to.z as i64 - from.z as i64, cell.z as i64),
+    ];
+    let n = axes.iter().map(|(_, d, _)| d.abs()).max()?;
+    if n == 0 {
+        return None;
+    }
+
+    // Along the longest axis, cell k is exactly k units away from `from`
+    let (start, delta, target) = axes.iter().find(|(_, d, _)| d.abs() == n)?;
+    let k = (target - start) * delta.signum();
+    if !(1..=n).contains(&k) {
+        return None;
+    }
+
+    axes.iter()
+        .all(|(start, delta, target)| start + delta * k / n == *target)
+        .then_some(k)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -357,6 +1018,328 @@ mod tests {
         assert_eq!(world.entity_count(), 0);
     }
 
+    #[test]
+    fn move_entity_across_zones() {
+        let mut world = World::new("Test".to_string(), RngSeed::new(1));
+        world.add_zone(Zone::new(ZoneId::new(1), None));
+
+        let id = world.allocate_entity_id();
+        world.add_entity(Entity::new(
+            id,
+            EntityKind::Creature,
+            WorldPos::origin(),
+            Tick::ZERO,
+            EntityProperties::default(),
+        ));
+
+        let to = WorldPos::new(ZoneId::new(1), sy_types::Position::new(3, 4, 0));
+        assert_eq!(world.move_entity(id, to), Some(WorldPos::origin()));
+
+        assert!(!world
+            .get_zone(ZoneId::ORIGIN)
+            .unwrap()
+            .entities
+            .contains(&id));
+        assert_eq!(world.get_zone(ZoneId::new(1)).unwrap().entities, vec![id]);
+        assert_eq!(world.get_entity(id).unwrap().position, to);
+    }
+
+    #[test]
+    fn unloaded_zone_entities_are_not_simulated() {
+        let mut world = World::new("Test".to_string(), RngSeed::new(1));
+        let mut zone = Zone::new(ZoneId::new(1), None);
+        zone.loaded = false;
+        world.add_zone(zone);
+
+        for zone_id in [ZoneId::ORIGIN, ZoneId::new(1)] {
+            let id = world.allocate_entity_id();
+            world.add_entity(Entity::new(
+                id,
+                EntityKind::Resource,
+                WorldPos::new(zone_id, sy_types::Position::ORIGIN),
+                Tick::ZERO,
+                EntityProperties::default(),
+            ));
+        }
+
+        let simulated: Vec<EntityId> = world.simulated_entities().map(|e| e.id).collect();
+        assert_eq!(simulated, vec![EntityId::new(1)]);
+        assert_eq!(world.active_entity_count(), 2);
+    }
+
+    #[test]
+    fn entity_components_roundtrip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Velocity {
+            dx: i32,
+            dy: i32,
+        }
+
+        impl Component for Velocity {
+            const NAME: &'static str = "velocity";
+        }
+
+        let mut world = World::new("Components".to_string(), RngSeed::new(7));
+        let id = world.allocate_entity_id();
+        let mut entity = Entity::new(
+            id,
+            EntityKind::Creature,
+            WorldPos::origin(),
+            Tick::ZERO,
+            EntityProperties::default(),
+        );
+        entity.set_component(&Velocity { dx: 1, dy: -2 }).unwrap();
+        world.add_entity(entity);
+
+        let restored = World::from_bytes(&world.to_bytes().unwrap()).unwrap();
+        assert_eq!(
+            restored.get_entity(id).unwrap().component::<Velocity>(),
+            Some(Velocity { dx: 1, dy: -2 })
+        );
+    }
+
+    #[test]
+    fn expiry_schedule_follows_entities() {
+        let mut world = World::new("Test".to_string(), RngSeed::new(1));
+        for _ in 0..3 {
+            let id = world.allocate_entity_id();
+            let mut entity = Entity::new(
+                id,
+                EntityKind::Item,
+                WorldPos::origin(),
+                Tick::ZERO,
+                EntityProperties::default(),
+            );
+            entity.expires_at = Some(Tick(10));
+            world.add_entity(entity);
+        }
+
+        assert_eq!(
+            world.set_entity_expiry(EntityId::new(1), Some(Tick(20))),
+            Some(Some(Tick(10)))
+        );
+        world.set_entity_expiry(EntityId::new(2), None);
+        world.remove_entity(EntityId::new(3));
+
+        assert!(world.expired_entities(Tick(19)).is_empty());
+        assert_eq!(world.expired_entities(Tick(20)), vec![EntityId::new(1)]);
+        assert_eq!(world.set_entity_expiry(EntityId::new(99), None), None);
+    }
+
+    #[test]
+    fn hierarchy_follows_moves_and_despawns() {
+        use sy_types::Position;
+
+        let mut world = World::new("Test".to_string(), RngSeed::new(1));
+        world.add_zone(Zone::new(ZoneId::new(1), None));
+        for x in 0..5 {
+            let id = world.allocate_entity_id();
+            world.add_entity(Entity::new(
+                id,
+                EntityKind::Item,
+                WorldPos::new(ZoneId::ORIGIN, Position::new(x, 0, 0)),
+                Tick::ZERO,
+                EntityProperties::default(),
+            ));
+        }
+        let attach = |world: &mut World, id: u64, parent: u64, policy| {
+            world.attach_entity(
+                EntityId::new(id),
+                Attachment {
+                    parent: EntityId::new(parent),
+                    on_parent_despawn: policy,
+                },
+            )
+        };
+
+        // 1 holds 2 (cascade) and 4 (orphan); 2 holds 3 (cascade)
+        attach(&mut world, 3, 2, OnParentDespawn::Cascade).unwrap();
+        attach(&mut world, 2, 1, OnParentDespawn::Cascade).unwrap();
+        attach(&mut world, 4, 5, OnParentDespawn::Orphan).unwrap();
+        assert_eq!(
+            attach(&mut world, 4, 1, OnParentDespawn::Orphan),
+            Ok(Some(EntityId::new(5)))
+        );
+        assert!(attach(&mut world, 1, 3, OnParentDespawn::Orphan).is_err());
+        assert_eq!(
+            world.descendants(EntityId::new(1)),
+            vec![EntityId::new(2), EntityId::new(3), EntityId::new(4)]
+        );
+
+        // Attached entities follow their parent, across zones too
+        let to = WorldPos::new(ZoneId::new(1), Position::new(9, 9, 0));
+        world.move_entity(EntityId::new(1), to);
+        assert_eq!(world.get_entity(EntityId::new(3)).unwrap().position, to);
+        assert_eq!(world.get_zone(ZoneId::new(1)).unwrap().entities.len(), 4);
+        assert_eq!(world.entities_within(ZoneId::new(1), to.pos, 0).len(), 4);
+
+        let restored = World::from_bytes(&world.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.children, world.children);
+
+        let events = world
+            .despawn_entity(EntityId::new(1), DespawnReason::Command)
+            .unwrap();
+        let despawned: Vec<EntityId> = events
+            .iter()
+            .filter_map(|e| match e {
+                EventData::EntityDespawned { entity_id, .. } => Some(*entity_id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            despawned,
+            vec![EntityId::new(3), EntityId::new(2), EntityId::new(1)]
+        );
+        assert!(world
+            .get_entity(EntityId::new(4))
+            .unwrap()
+            .parent()
+            .is_none());
+        assert!(world.children.is_empty());
+    }
+
+    #[test]
+    fn spatial_index_follows_entities() {
+        use sy_types::Position;
+
+        let mut world = World::new("Test".to_string(), RngSeed::new(1));
+        world.add_zone(Zone::new(ZoneId::new(1), None));
+        for (x, kind) in [
+            (0, EntityKind::Creature),
+            (5, EntityKind::Item),
+            (40, EntityKind::Creature),
+        ] {
+            let id = world.allocate_entity_id();
+            world.add_entity(Entity::new(
+                id,
+                kind,
+                WorldPos::new(ZoneId::ORIGIN, Position::new(x, 0, 0)),
+                Tick::ZERO,
+                EntityProperties::default(),
+            ));
+        }
+
+        world.move_entity(
+            EntityId::new(1),
+            WorldPos::new(ZoneId::new(1), Position::ORIGIN),
+        );
+        world.move_entity(
+            EntityId::new(3),
+            WorldPos::new(ZoneId::ORIGIN, Position::new(6, 0, 0)),
+        );
+
+        let origin = ZoneId::ORIGIN;
+        assert_eq!(
+            world.entities_within(origin, Position::ORIGIN, 10),
+            vec![EntityId::new(2), EntityId::new(3)]
+        );
+        assert_eq!(
+            world.nearest_of_kind(origin, Position::ORIGIN, EntityKind::Creature, 5),
+            vec![EntityId::new(3)]
+        );
+        assert_eq!(
+            world.entities_in_box(
+                ZoneId::new(1),
+                Position::new(-1, -1, -1),
+                Position::new(1, 1, 1)
+            ),
+            vec![EntityId::new(1)]
+        );
+
+        world.remove_entity(EntityId::new(2));
+        let restored = World::from_bytes(&world.to_bytes().unwrap()).unwrap();
+        assert_eq!(
+            restored.entities_within(origin, Position::ORIGIN, 10),
+            vec![EntityId::new(3)]
+        );
+        assert_eq!(restored.spatial_index(ZoneId::new(1)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn steps_cross_every_cell_along_their_longest_axis() {
+        use sy_types::Position;
+
+        let from = Position::ORIGIN;
+        assert_eq!(
+            step_index(from, Position::new(3, 0, 0), Position::new(1, 0, 0)),
+            Some(1)
+        );
+        assert_eq!(
+            step_index(from, Position::new(3, 0, 0), Position::new(3, 0, 0)),
+            Some(3)
+        );
+        assert_eq!(step_index(from, Position::new(3, 0, 0), from), None);
+        assert_eq!(
+            step_index(from, Position::new(3, 0, 0), Position::new(4, 0, 0)),
+            None
+        );
+        assert_eq!(
+            step_index(from, Position::new(-2, -2, 0), Position::new(-1, -1, 0)),
+            Some(1)
+        );
+        assert_eq!(
+            step_index(from, Position::new(-2, -2, 0), Position::new(-1, 0, 0)),
+            None
+        );
+        // Shorter axes advance by whole units, rounded towards the start
+        assert_eq!(
+            step_index(from, Position::new(4, 2, 0), Position::new(1, 0, 0)),
+            Some(1)
+        );
+        assert_eq!(
+            step_index(from, Position::new(4, 2, 0), Position::new(2, 1, 0)),
+            Some(2)
+        );
+        assert_eq!(step_index(from, from, from), None);
+    }
+
+    #[test]
+    fn portals_link_and_unlink_zones() {
+        use sy_types::{Portal, Position};
+
+        let mut world = World::new("Test".to_string(), RngSeed::new(1));
+        world.add_zone(Zone::new(ZoneId::new(1), None));
+        let portal = Portal {
+            from: Position::new(10, 0, 0),
+            to: WorldPos::new(ZoneId::new(1), Position::new(0, 5, 0)),
+        };
+
+        assert!(!world.add_portal(
+            ZoneId::ORIGIN,
+            Portal {
+                to: WorldPos::new(ZoneId::new(9), Position::ORIGIN),
+                ..portal
+            }
+        ));
+        assert!(world.add_portal(ZoneId::ORIGIN, portal));
+        assert!(world
+            .get_zone(ZoneId::new(1))
+            .unwrap()
+            .neighbours
+            .contains(&ZoneId::ORIGIN));
+
+        let entrance = WorldPos::new(ZoneId::ORIGIN, portal.from);
+        assert_eq!(world.resolve_portal(entrance), portal.to);
+        let elsewhere = WorldPos::new(ZoneId::ORIGIN, Position::new(11, 0, 0));
+        assert_eq!(world.resolve_portal(elsewhere), elsewhere);
+
+        // Stepping over the entrance uses it too
+        let over = world.resolve_step(
+            ZoneId::ORIGIN,
+            Position::new(8, 0, 0),
+            Position::new(12, 0, 0),
+        );
+        assert_eq!(over, portal.to);
+
+        assert!(world.unlink_zones(ZoneId::new(1), ZoneId::ORIGIN));
+        assert_eq!(world.resolve_portal(entrance), entrance);
+        assert!(world
+            .get_zone(ZoneId::ORIGIN)
+            .unwrap()
+            .neighbours
+            .is_empty());
+    }
+
     #[test]
     fn world_serialization() {
         let world = World::new("Serialize Test".to_string(), RngSeed::new(123));
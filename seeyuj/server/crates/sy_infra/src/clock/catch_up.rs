@@ -0,0 +1,146 @@
+//! # Catch-up
+//!
+//! Offline catch-up: how many ticks a world missed while its server was down.
+//!
+//! ## Design
+//! - The wall-clock anchor is persisted next to the world (`anchor.json`,
+//!   see `FilesystemStore`), never in `World`: the core stays free of
+//!   wall-clock time and replay stays deterministic
+//! - The anchor states "the world should be at `tick` at `unix_ms`"; the
+//!   expected tick at any later time follows from the tick rate
+//! - The plan is capped: past the cap, missed ticks are skipped for good
+//!   (the anchor is moved so they are not counted again)
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sy_types::Tick;
+
+/// Milliseconds since the Unix epoch, from the system clock.
+pub fn unix_now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Wall-clock time at which the world is (or should be) at a given tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WallClockAnchor {
+    /// Simulation tick
+    pub tick: Tick,
+    /// Wall-clock time, in milliseconds since the Unix epoch
+    pub unix_ms: u64,
+}
+
+impl WallClockAnchor {
+    pub fn new(tick: Tick, unix_ms: u64) -> Self {
+        WallClockAnchor { tick, unix_ms }
+    }
+
+    /// Anchor `tick` at the current system time.
+    pub fn now(tick: Tick) -> Self {
+        WallClockAnchor::new(tick, unix_now_ms())
+    }
+
+    /// Tick the world should have reached at `now_ms`, running at
+    /// `ticks_per_second` since the anchor.
+    pub fn expected_tick(&self, now_ms: u64, ticks_per_second: u32) -> Tick {
+        let elapsed_ms = now_ms.saturating_sub(self.unix_ms) as u128;
+        let ticks = elapsed_ms * ticks_per_second as u128 / 1000;
+        Tick(
+            self.tick
+                .as_u64()
+                .saturating_add(ticks.min(u64::MAX as u128) as u64),
+        )
+    }
+}
+
+/// How missed ticks are made up on restart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatchUpPolicy {
+    /// Tick rate of the world
+    pub ticks_per_second: u32,
+    /// Maximum number of ticks run to catch up (the rest is skipped)
+    pub max_ticks: u64,
+}
+
+/// Outcome of a catch-up decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatchUpPlan {
+    /// Wall-clock time the world is behind, in milliseconds
+    pub offline_ms: u64,
+    /// Ticks missed
+    pub missed_ticks: u64,
+    /// Ticks to run now (at most `max_ticks`)
+    pub ticks: u64,
+    /// New anchor: the world is considered on time once `ticks` have run
+    pub anchor: WallClockAnchor,
+}
+
+impl CatchUpPlan {
+    /// Missed ticks that will never be run.
+    pub fn skipped_ticks(&self) -> u64 {
+        self.missed_ticks - self.ticks
+    }
+}
+
+impl CatchUpPolicy {
+    /// Decide how to catch up a world at `current` given its last anchor.
+    pub fn plan(&self, anchor: WallClockAnchor, current: Tick, now_ms: u64) -> CatchUpPlan {
+        let expected = anchor.expected_tick(now_ms, self.ticks_per_second);
+        let missed_ticks = expected.as_u64().saturating_sub(current.as_u64());
+        let ticks = missed_ticks.min(self.max_ticks);
+        let offline_ms = if self.ticks_per_second == 0 {
+            0
+        } else {
+            (missed_ticks as u128 * 1000 / self.ticks_per_second as u128) as u64
+        };
+
+        CatchUpPlan {
+            offline_ms,
+            missed_ticks,
+            ticks,
+            anchor: WallClockAnchor::new(Tick(current.as_u64() + ticks), now_ms),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: CatchUpPolicy = CatchUpPolicy {
+        ticks_per_second: 20,
+        max_ticks: 1000,
+    };
+
+    #[test]
+    fn missed_ticks_follow_wall_clock() {
+        let anchor = WallClockAnchor::new(Tick(100), 1_000_000);
+
+        // 10 s offline at 20 TPS, but the WAL already replayed 50 ticks
+        let plan = POLICY.plan(anchor, Tick(150), 1_010_000);
+        assert_eq!(plan.missed_ticks, 150);
+        assert_eq!(plan.ticks, 150);
+        assert_eq!(plan.skipped_ticks(), 0);
+        assert_eq!(plan.offline_ms, 7_500);
+        assert_eq!(plan.anchor, WallClockAnchor::new(Tick(300), 1_010_000));
+
+        // Clock moved backwards: nothing to do
+        let plan = POLICY.plan(anchor, Tick(100), 900_000);
+        assert_eq!(plan.missed_ticks, 0);
+    }
+
+    #[test]
+    fn catch_up_is_capped() {
+        let anchor = WallClockAnchor::new(Tick(0), 0);
+        let plan = POLICY.plan(anchor, Tick(0), 3_600_000);
+
+        assert_eq!(plan.missed_ticks, 72_000);
+        assert_eq!(plan.ticks, 1000);
+        assert_eq!(plan.skipped_ticks(), 71_000);
+        // Skipped ticks are not counted again next time
+        assert_eq!(plan.anchor.expected_tick(3_600_000, 20), Tick(1000));
+    }
+}
@@ -5,6 +5,12 @@
 //! ## Implementations
 //! - `UnlimitedClock`: Runs as fast as possible (for headless/batch)
 //! - `FixedStepClock`: Runs at a fixed tick rate (for real-time)
+//!
+//! `catch_up` computes the ticks missed while the server was down.
+
+mod catch_up;
+
+pub use catch_up::{unix_now_ms, CatchUpPlan, CatchUpPolicy, WallClockAnchor};
 
 use std::time::{Duration, Instant};
 
@@ -18,6 +18,6 @@ pub mod rng;
 pub mod store;
 
 // Re-exports
-pub use clock::{FixedStepClock, UnlimitedClock};
+pub use clock::{CatchUpPlan, CatchUpPolicy, FixedStepClock, UnlimitedClock, WallClockAnchor};
 pub use rng::Pcg32Rng;
 pub use store::{FileEventLog, FilesystemStore};
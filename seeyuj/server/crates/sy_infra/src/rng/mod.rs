@@ -8,7 +8,7 @@
 //! - State: 64 bits
 //! - Output: 32 bits per call
 
-use sy_core::ports::IRng;
+use sy_core::ports::{stream_key, IRng};
 use sy_types::RngSeed;
 
 /// PCG32 random number generator.
@@ -58,6 +58,12 @@ impl IRng for Pcg32Rng {
         self.state = state;
     }
 
+    fn split(&self, stream: u64) -> Box<dyn IRng> {
+        Box::new(Pcg32Rng::new(RngSeed::new(stream_key(&[
+            self.state, stream,
+        ]))))
+    }
+
     fn next_u32(&mut self) -> u32 {
         let old_state = self.state;
         self.advance();
@@ -78,6 +84,7 @@ impl IRng for Pcg32Rng {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sy_types::{Fixed, Probability};
 
     #[test]
     fn deterministic_sequence() {
@@ -122,6 +129,21 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn split_streams_are_independent() {
+        let rng = Pcg32Rng::new(RngSeed::new(42));
+        let mut a = rng.split(1);
+        let mut b = rng.split(2);
+
+        assert_eq!(a.next_u64(), rng.split(1).next_u64());
+        assert_ne!(a.next_u64(), b.next_u64());
+
+        // Splitting does not advance the parent
+        let state = rng.state();
+        rng.split(3);
+        assert_eq!(rng.state(), state);
+    }
+
     #[test]
     fn range_bounds() {
         let mut rng = Pcg32Rng::new(RngSeed::new(12345));
@@ -131,4 +153,50 @@ mod tests {
             assert!((-10..=10).contains(&val));
         }
     }
+
+    #[test]
+    fn chance_follows_integer_ratio() {
+        let mut rng = Pcg32Rng::new(RngSeed::new(7));
+
+        let hits = (0..10_000)
+            .filter(|_| rng.chance(Probability::new(1, 4)))
+            .count();
+        assert!((2_300..2_700).contains(&hits));
+
+        assert!((0..1000).all(|_| rng.chance(Probability::ALWAYS)));
+        assert!(!(0..1000).any(|_| rng.chance(Probability::NEVER)));
+
+        // One draw per call, whatever the probability
+        let state = rng.state();
+        rng.chance(Probability::ALWAYS);
+        let after_always = rng.state();
+        rng.restore(state);
+        rng.next_u32();
+        assert_eq!(rng.state(), after_always);
+    }
+
+    #[test]
+    fn expected_successes_round_the_expectation() {
+        let mut rng = Pcg32Rng::new(RngSeed::new(11));
+
+        assert_eq!(rng.expected_successes(200, Probability::new(1, 100)), 2);
+        assert_eq!(
+            rng.expected_successes(u64::MAX, Probability::ALWAYS),
+            u64::MAX
+        );
+        for _ in 0..100 {
+            let n = rng.expected_successes(250, Probability::new(1, 100));
+            assert!(n == 2 || n == 3);
+        }
+    }
+
+    #[test]
+    fn next_fixed_in_unit_interval() {
+        let mut rng = Pcg32Rng::new(RngSeed::new(9));
+
+        for _ in 0..1000 {
+            let val = rng.next_fixed();
+            assert!(val >= Fixed::ZERO && val < Fixed::ONE);
+        }
+    }
 }
@@ -17,6 +17,8 @@ use sy_core::ports::{IWorldStore, WorldSnapshot};
 use sy_types::{SimError, SimResult, WorldMeta};
 use tracing::{debug, info, warn};
 
+use crate::clock::WallClockAnchor;
+
 /// Filesystem-based world store.
 ///
 /// Directory structure:
@@ -26,7 +28,9 @@ use tracing::{debug, info, warn};
 ///     {world_id}/
 ///       meta.json      - World metadata
 ///       snapshot.json  - World state snapshot
+///       anchor.json    - Wall-clock anchor (offline catch-up, server_d)
 ///       events/        - Event log directory
+///       inbox/         - External inputs waiting to be queued (server_d)
 /// ```
 pub struct FilesystemStore {
     base_path: PathBuf,
@@ -81,6 +85,55 @@ impl FilesystemStore {
     pub fn events_dir(&self, world_id: &str) -> PathBuf {
         self.world_dir(world_id).join("events")
     }
+
+    /// Get the input inbox directory for a world.
+    pub fn inbox_dir(&self, world_id: &str) -> PathBuf {
+        self.world_dir(world_id).join("inbox")
+    }
+
+    /// Get the wall-clock anchor file path for a world.
+    fn anchor_path(&self, world_id: &str) -> PathBuf {
+        self.world_dir(world_id).join("anchor.json")
+    }
+
+    /// Load the wall-clock anchor of a world (None if never saved).
+    /// Kept outside the world state: the core never sees wall-clock time.
+    pub fn load_anchor(&self, world_id: &str) -> SimResult<Option<WallClockAnchor>> {
+        let path = self.anchor_path(world_id);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| SimError::PersistenceError(format!("Failed to read anchor: {}", e)))?;
+        let anchor = serde_json::from_str(&contents)
+            .map_err(|e| SimError::PersistenceError(format!("Failed to parse anchor: {}", e)))?;
+        Ok(Some(anchor))
+    }
+
+    /// Save the wall-clock anchor of a world (atomic write).
+    pub fn save_anchor(&self, world_id: &str, anchor: &WallClockAnchor) -> SimResult<()> {
+        self.ensure_world_dir(world_id)?;
+
+        let path = self.anchor_path(world_id);
+        let temp_path = path.with_extension("json.tmp");
+        let contents = serde_json::to_vec(anchor).map_err(|e| {
+            SimError::PersistenceError(format!("Failed to serialize anchor: {}", e))
+        })?;
+
+        let mut file = File::create(&temp_path).map_err(|e| {
+            SimError::PersistenceError(format!("Failed to create anchor file: {}", e))
+        })?;
+        file.write_all(&contents)
+            .map_err(|e| SimError::PersistenceError(format!("Failed to write anchor: {}", e)))?;
+        file.sync_all()
+            .map_err(|e| SimError::PersistenceError(format!("Failed to sync anchor: {}", e)))?;
+        fs::rename(&temp_path, &path)
+            .map_err(|e| SimError::PersistenceError(format!("Failed to rename anchor: {}", e)))?;
+
+        debug!("Saved anchor for world {} at {}", world_id, anchor.tick);
+        Ok(())
+    }
 }
 
 impl IWorldStore for FilesystemStore {
@@ -280,6 +333,7 @@ mod tests {
             snapshot_tick: Tick(100),
             last_event_id: EventId::new(50),
             format_version: WorldMeta::CURRENT_FORMAT_VERSION,
+            forked_from: None,
         };
 
         store.save_meta(&meta).unwrap();
@@ -307,6 +361,7 @@ mod tests {
             snapshot_tick: Tick::ZERO,
             last_event_id: EventId::ZERO,
             format_version: WorldMeta::CURRENT_FORMAT_VERSION,
+            forked_from: None,
         };
         store.save_meta(&meta).unwrap();
 
@@ -318,4 +373,14 @@ mod tests {
         let loaded = store.load_snapshot("snapshot_test").unwrap();
         assert_eq!(loaded, snapshot);
     }
+
+    #[test]
+    fn save_load_anchor() {
+        let store = temp_store();
+        assert_eq!(store.load_anchor("anchor_test").unwrap(), None);
+
+        let anchor = WallClockAnchor::new(Tick(42), 1_700_000_000_000);
+        store.save_anchor("anchor_test", &anchor).unwrap();
+        assert_eq!(store.load_anchor("anchor_test").unwrap(), Some(anchor));
+    }
 }
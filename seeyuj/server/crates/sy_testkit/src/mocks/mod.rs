@@ -10,7 +10,7 @@ use std::collections::HashMap;
 
 use sy_api::events::SimEvent;
 use sy_core::ports::{
-    IEventLog, IRng, ISimClock, IStateHasher, IWorldStore, StateHash, WorldSnapshot,
+    stream_key, IEventLog, IRng, ISimClock, IStateHasher, IWorldStore, StateHash, WorldSnapshot,
 };
 use sy_types::{EventId, RngSeed, SimError, SimResult, SimTime, Tick, WorldMeta};
 
@@ -46,6 +46,12 @@ impl IRng for MockRng {
         self.state = state;
     }
 
+    fn split(&self, stream: u64) -> Box<dyn IRng> {
+        Box::new(MockRng::new(RngSeed::new(stream_key(&[
+            self.state, stream,
+        ]))))
+    }
+
     fn next_u32(&mut self) -> u32 {
         // Simple LCG for testing
         self.state = self.state.wrapping_mul(6364136223846793005).wrapping_add(1);
@@ -113,6 +119,7 @@ impl ISimClock for MockClock {
 pub struct MockEventLog {
     events: Vec<SimEvent>,
     next_event_id: u64,
+    batches: usize,
 }
 
 impl MockEventLog {
@@ -120,8 +127,14 @@ impl MockEventLog {
         MockEventLog {
             events: Vec::new(),
             next_event_id: 1,
+            batches: 0,
         }
     }
+
+    /// Number of `append_batch` calls (one per WAL batch).
+    pub fn batch_count(&self) -> usize {
+        self.batches
+    }
 }
 
 impl Default for MockEventLog {
@@ -139,6 +152,7 @@ impl IEventLog for MockEventLog {
     }
 
     fn append_batch(&mut self, events: Vec<SimEvent>) -> SimResult<Vec<SimEvent>> {
+        self.batches += 1;
         let mut persisted = Vec::with_capacity(events.len());
         for event in events {
             persisted.push(self.append(event)?);
@@ -7,7 +7,11 @@
 //! let sim = TestScenario::empty_world(42).build();
 //! ```
 
-use sy_api::commands::{Command, CreateWorldCmd, EntityProperties, SpawnEntityCmd};
+use sy_api::archetypes::Archetype;
+use sy_api::commands::{
+    Command, CreateWorldCmd, EntityProperties, SpawnEntityCmd, SpawnFromArchetypeCmd,
+};
+use sy_api::components::Components;
 use sy_core::Simulation;
 use sy_types::{EntityKind, Position, RngSeed, WorldPos, ZoneId};
 
@@ -17,7 +21,8 @@ use crate::mocks::{MockClock, MockEventLog, MockRng, MockWorldStore};
 pub struct TestScenario {
     seed: RngSeed,
     world_name: String,
-    entities_to_spawn: Vec<SpawnEntityCmd>,
+    archetypes: Vec<Archetype>,
+    entities_to_spawn: Vec<Command>,
 }
 
 impl TestScenario {
@@ -26,6 +31,7 @@ impl TestScenario {
         TestScenario {
             seed: RngSeed::new(seed),
             world_name: "Test World".to_string(),
+            archetypes: Vec::new(),
             entities_to_spawn: Vec::new(),
         }
     }
@@ -38,29 +44,76 @@ impl TestScenario {
 
     /// Add a resource entity.
     pub fn with_resource(mut self, x: i32, y: i32, amount: u32) -> Self {
-        self.entities_to_spawn.push(SpawnEntityCmd {
-            position: WorldPos::new(ZoneId::ORIGIN, Position::new(x, y, 0)),
-            kind: EntityKind::Resource,
-            properties: EntityProperties {
-                name: Some("Resource".to_string()),
-                amount: Some(amount),
-                health: None,
-            },
-        });
+        self.entities_to_spawn
+            .push(Command::SpawnEntity(SpawnEntityCmd {
+                position: WorldPos::new(ZoneId::ORIGIN, Position::new(x, y, 0)),
+                kind: EntityKind::Resource,
+                properties: EntityProperties {
+                    name: Some("Resource".to_string()),
+                    amount: Some(amount),
+                    health: None,
+                },
+                components: Components::default(),
+                expires_at: None,
+            }));
         self
     }
 
     /// Add a creature entity.
     pub fn with_creature(mut self, x: i32, y: i32, health: u32) -> Self {
-        self.entities_to_spawn.push(SpawnEntityCmd {
-            position: WorldPos::new(ZoneId::ORIGIN, Position::new(x, y, 0)),
-            kind: EntityKind::Creature,
-            properties: EntityProperties {
-                name: Some("Creature".to_string()),
-                amount: None,
-                health: Some(health),
-            },
-        });
+        self.entities_to_spawn
+            .push(Command::SpawnEntity(SpawnEntityCmd {
+                position: WorldPos::new(ZoneId::ORIGIN, Position::new(x, y, 0)),
+                kind: EntityKind::Creature,
+                properties: EntityProperties {
+                    name: Some("Creature".to_string()),
+                    amount: None,
+                    health: Some(health),
+                },
+                components: Components::default(),
+                expires_at: None,
+            }));
+        self
+    }
+
+    /// Add a structure entity.
+    pub fn with_structure(mut self, x: i32, y: i32) -> Self {
+        self.entities_to_spawn
+            .push(Command::SpawnEntity(SpawnEntityCmd {
+                position: WorldPos::new(ZoneId::ORIGIN, Position::new(x, y, 0)),
+                kind: EntityKind::Structure,
+                properties: EntityProperties {
+                    name: Some("Structure".to_string()),
+                    amount: None,
+                    health: None,
+                },
+                components: Components::default(),
+                expires_at: None,
+            }));
+        self
+    }
+
+    /// Add an arbitrary entity.
+    pub fn with_entity(mut self, cmd: SpawnEntityCmd) -> Self {
+        self.entities_to_spawn.push(Command::SpawnEntity(cmd));
+        self
+    }
+
+    /// Register an archetype in the world (before any entity is spawned).
+    pub fn with_archetype(mut self, archetype: Archetype) -> Self {
+        self.archetypes.push(archetype);
+        self
+    }
+
+    /// Add an entity built from a registered archetype.
+    pub fn with_archetype_entity(mut self, archetype: &str, x: i32, y: i32) -> Self {
+        self.entities_to_spawn
+            .push(Command::SpawnFromArchetype(SpawnFromArchetypeCmd {
+                archetype: archetype.to_string(),
+                position: WorldPos::new(ZoneId::ORIGIN, Position::new(x, y, 0)),
+                properties: EntityProperties::default(),
+                expires_at: None,
+            }));
         self
     }
 
@@ -80,9 +133,14 @@ impl TestScenario {
         }))
         .expect("Failed to create test world");
 
+        for archetype in self.archetypes {
+            sim.process_command(Command::DefineArchetype(archetype))
+                .expect("Failed to define archetype");
+        }
+
         // Spawn entities
         for spawn_cmd in self.entities_to_spawn {
-            sim.process_command(Command::SpawnEntity(spawn_cmd))
+            sim.process_command(spawn_cmd)
                 .expect("Failed to spawn entity");
         }
 
@@ -93,6 +151,23 @@ impl TestScenario {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
+    use sy_api::commands::{
+        AddPortalCmd, AttachEntityCmd, CreateZoneCmd, LoadWorldCmd, MoveEntityCmd, QueueInputCmd,
+        ScheduleCmd, SetEntityExpiryCmd, SetEntityPropertyCmd,
+    };
+    use sy_api::components::{MoveTarget, Tags, Velocity};
+    use sy_api::errors::ApiError;
+    use sy_api::events::{DespawnReason, EventData, PropertyValue, SimEvent};
+    use sy_core::ports::{IEventLog, IWorldStore};
+    use sy_core::systems::{
+        CreatureDegradationSystem, IdleDormancySystem, NeighbourWakeSystem, ResourceDepletionSystem,
+    };
+    use sy_core::{
+        compute_canonical_hash, replay_events, state_at, EventFilter, ForkAt, ITickSystem,
+        SystemId, TickContext, World, XxHasher,
+    };
+    use sy_types::{EntityId, EntityState, EventId, OnParentDespawn, Portal, SourceId, Tick};
 
     #[test]
     fn empty_world_scenario() {
@@ -110,4 +185,1437 @@ mod tests {
         let world = sim.world().unwrap();
         assert_eq!(world.entity_count(), 2);
     }
+
+    /// Rebuild the world from the last snapshot + WAL, as LoadWorld does.
+    fn recover(sim: &Simulation<MockRng, MockClock, MockEventLog, MockWorldStore>) -> World {
+        let world_id = sim.world().unwrap().id().to_string();
+        let snapshot = sim.store().load_snapshot(&world_id).unwrap();
+        let mut world = World::from_bytes(&snapshot).unwrap();
+        let events = sim
+            .event_log()
+            .read_from_event_id(world.meta.last_event_id)
+            .unwrap();
+        replay_events(&mut world, &events);
+        world
+    }
+
+    #[test]
+    fn movement_survives_crash_recovery() {
+        let mut sim = TestScenario::empty_world(7)
+            .with_entity(SpawnEntityCmd {
+                position: WorldPos::origin(),
+                kind: EntityKind::Creature,
+                properties: EntityProperties::default(),
+                components: Components::new()
+                    .with(&Velocity {
+                        dx: 1,
+                        dy: 2,
+                        dz: 0,
+                    })
+                    .unwrap(),
+                expires_at: None,
+            })
+            .build();
+        sim.process_command(Command::CreateZone(CreateZoneCmd {
+            zone_id: ZoneId::new(1),
+            name: None,
+        }))
+        .unwrap();
+        sim.process_command(Command::SaveWorld).unwrap();
+
+        sim.process_command(Command::TickN(5)).unwrap();
+        sim.process_command(Command::MoveEntity(MoveEntityCmd {
+            entity_id: EntityId::new(1),
+            to: WorldPos::new(ZoneId::new(1), Position::new(-3, 0, 0)),
+        }))
+        .unwrap();
+        sim.process_command(Command::TickN(2)).unwrap();
+
+        let live = sim.world().unwrap();
+        let entity = live.get_entity(EntityId::new(1)).unwrap();
+        assert_eq!(
+            entity.position,
+            WorldPos::new(ZoneId::new(1), Position::new(-1, 4, 0))
+        );
+
+        let recovered = recover(&sim);
+        for (id, zone) in &live.zones {
+            assert_eq!(recovered.zones[id].entities, zone.entities);
+        }
+        assert_eq!(
+            recovered.get_entity(EntityId::new(1)).unwrap().position,
+            entity.position
+        );
+    }
+
+    #[test]
+    fn unloaded_zone_is_frozen() {
+        let mut sim = TestScenario::empty_world(3)
+            .with_resource(0, 0, 100)
+            .build();
+        sim.process_command(Command::CreateZone(CreateZoneCmd {
+            zone_id: ZoneId::new(1),
+            name: None,
+        }))
+        .unwrap();
+        sim.process_command(Command::SpawnEntity(SpawnEntityCmd {
+            position: WorldPos::new(ZoneId::new(1), Position::ORIGIN),
+            kind: EntityKind::Resource,
+            properties: EntityProperties {
+                name: None,
+                amount: Some(100),
+                health: None,
+            },
+            components: Components::default(),
+            expires_at: None,
+        }))
+        .unwrap();
+
+        let events = sim
+            .process_command(Command::UnloadZone(ZoneId::new(1)))
+            .unwrap();
+        assert!(matches!(events[0].data, EventData::ZoneUnloaded { .. }));
+
+        let events = sim.process_command(Command::TickN(500)).unwrap();
+        assert!(events.iter().all(|e| match &e.data {
+            EventData::TickProcessed {
+                entities_processed, ..
+            } => *entities_processed == 1,
+            _ => true,
+        }));
+
+        let world = sim.world().unwrap();
+        let frozen = world.get_entity(EntityId::new(2)).unwrap();
+        assert_eq!(frozen.properties.amount, Some(100));
+        let live = world.get_entity(EntityId::new(1)).unwrap();
+        assert!(live.properties.amount < Some(100));
+
+        // Unloading twice is a no-op
+        let events = sim
+            .process_command(Command::UnloadZone(ZoneId::new(1)))
+            .unwrap();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn coarse_steps_advance_unloaded_zones_and_survive_crash_recovery() {
+        let mut sim = TestScenario::empty_world(3).build();
+        sim.set_strict(true);
+        sim.set_coarse_interval(200);
+        sim.process_command(Command::CreateZone(CreateZoneCmd {
+            zone_id: ZoneId::new(1),
+            name: None,
+        }))
+        .unwrap();
+        for (kind, amount, health) in [
+            (EntityKind::Resource, Some(100), None),
+            (EntityKind::Creature, None, Some(100)),
+        ] {
+            sim.process_command(Command::SpawnEntity(SpawnEntityCmd {
+                position: WorldPos::new(ZoneId::new(1), Position::ORIGIN),
+                kind,
+                properties: EntityProperties {
+                    name: None,
+                    amount,
+                    health,
+                },
+                components: Components::default(),
+                expires_at: None,
+            }))
+            .unwrap();
+        }
+        sim.process_command(Command::UnloadZone(ZoneId::new(1)))
+            .unwrap();
+        sim.process_command(Command::TickN(100)).unwrap();
+        sim.process_command(Command::SaveWorld).unwrap();
+
+        // One step over ticks 0..200: exactly the expected losses
+        let events = sim.process_command(Command::TickN(100)).unwrap();
+        let step = events
+            .iter()
+            .position(|e| matches!(e.data, EventData::ZoneCoarseStepped { .. }))
+            .unwrap();
+        assert!(matches!(
+            events[step].data,
+            EventData::ZoneCoarseStepped {
+                from: Tick(0),
+                to: Tick(200),
+                ..
+            }
+        ));
+        assert!(matches!(
+            events[step + 1].data,
+            EventData::ResourceDepleted {
+                amount: 2,
+                remaining: 98,
+                ..
+            }
+        ));
+        assert!(matches!(
+            events[step + 2].data,
+            EventData::EntityDegraded { new_health: 99, .. }
+        ));
+
+        // Loading reconciles the ticks since the last step
+        sim.process_command(Command::TickN(50)).unwrap();
+        let events = sim
+            .process_command(Command::LoadZone(ZoneId::new(1)))
+            .unwrap();
+        assert!(matches!(
+            events[0].data,
+            EventData::ZoneCoarseStepped {
+                from: Tick(200),
+                to: Tick(250),
+                ..
+            }
+        ));
+        assert!(matches!(
+            events.last().unwrap().data,
+            EventData::ZoneLoaded { .. }
+        ));
+        assert!(sim
+            .world()
+            .unwrap()
+            .get_zone(ZoneId::new(1))
+            .is_some_and(|z| z.loaded && z.simulated_until == Tick(250)));
+
+        sim.process_command(Command::TickN(10)).unwrap();
+        let recovered = recover(&sim);
+        assert_eq!(
+            compute_canonical_hash(&recovered, &mut XxHasher::new()),
+            compute_canonical_hash(sim.world().unwrap(), &mut XxHasher::new())
+        );
+    }
+
+    #[test]
+    fn sleep_and_wake_commands() {
+        let mut sim = TestScenario::empty_world(5)
+            .with_creature(0, 0, 100)
+            .build();
+        let id = EntityId::new(1);
+
+        let events = sim.process_command(Command::SleepEntity(id)).unwrap();
+        assert!(matches!(
+            events[0].data,
+            EventData::EntityStateChanged {
+                new_state: EntityState::Dormant,
+                ..
+            }
+        ));
+
+        let events = sim.process_command(Command::TickN(50)).unwrap();
+        assert!(events.iter().all(|e| e.data.entity_id().is_none()));
+
+        let events = sim.process_command(Command::WakeEntity(id)).unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(sim.world().unwrap().get_entity(id).unwrap().is_active());
+    }
+
+    #[test]
+    fn dormancy_rules_survive_crash_recovery() {
+        let mut sim = TestScenario::empty_world(11)
+            .with_structure(0, 0)
+            .with_structure(2, 0)
+            .with_structure(50, 0)
+            .build();
+        sim.register_system(Box::new(IdleDormancySystem::new(10)));
+        sim.register_system(Box::new(NeighbourWakeSystem::new(3)));
+        sim.process_command(Command::SaveWorld).unwrap();
+
+        sim.process_command(Command::TickN(10)).unwrap();
+        assert!(sim
+            .world()
+            .unwrap()
+            .entities
+            .values()
+            .all(|e| e.is_dormant()));
+
+        // Moving E1 wakes its close neighbour E2 on the next tick, not E3
+        sim.process_command(Command::WakeEntity(EntityId::new(1)))
+            .unwrap();
+        sim.process_command(Command::MoveEntity(MoveEntityCmd {
+            entity_id: EntityId::new(1),
+            to: WorldPos::new(ZoneId::ORIGIN, Position::new(1, 0, 0)),
+        }))
+        .unwrap();
+        sim.process_command(Command::Tick).unwrap();
+
+        let world = sim.world().unwrap();
+        assert!(world.get_entity(EntityId::new(2)).unwrap().is_active());
+        assert!(world.get_entity(EntityId::new(3)).unwrap().is_dormant());
+
+        sim.process_command(Command::TickN(20)).unwrap();
+
+        let mut hasher = XxHasher::new();
+        let live = compute_canonical_hash(sim.world().unwrap(), &mut hasher);
+        let recovered = compute_canonical_hash(&recover(&sim), &mut hasher);
+        assert_eq!(live, recovered);
+    }
+
+    #[test]
+    fn neighbour_wake_does_not_chain_through_dormant_entities() {
+        let mut sim = TestScenario::empty_world(14)
+            .with_creature(0, 0, 100)
+            .with_structure(3, 0)
+            .with_structure(6, 0)
+            .with_structure(9, 0)
+            .build();
+        sim.unregister_system(CreatureDegradationSystem::ID);
+        sim.register_system(Box::new(NeighbourWakeSystem::new(3)));
+        sim.process_command(Command::SaveWorld).unwrap();
+        for id in 2..=4 {
+            sim.process_command(Command::SleepEntity(EntityId::new(id)))
+                .unwrap();
+        }
+        sim.process_command(Command::TickN(5)).unwrap();
+
+        // E1 changes: E2 (3 away) wakes on the next tick
+        sim.process_command(Command::SetEntityProperty(SetEntityPropertyCmd {
+            entity_id: EntityId::new(1),
+            property: "name".to_string(),
+            value: PropertyValue::String("Rex".to_string()),
+        }))
+        .unwrap();
+        sim.process_command(Command::Tick).unwrap();
+        assert!(sim
+            .world()
+            .unwrap()
+            .get_entity(EntityId::new(2))
+            .unwrap()
+            .is_active());
+
+        // Being woken is not a change: E3 and E4 stay asleep
+        sim.process_command(Command::TickN(10)).unwrap();
+        let world = sim.world().unwrap();
+        assert!(world.get_entity(EntityId::new(3)).unwrap().is_dormant());
+        assert!(world.get_entity(EntityId::new(4)).unwrap().is_dormant());
+
+        let mut hasher = XxHasher::new();
+        let live = compute_canonical_hash(world, &mut hasher);
+        let recovered = compute_canonical_hash(&recover(&sim), &mut hasher);
+        assert_eq!(live, recovered);
+    }
+
+    #[test]
+    fn neighbour_wake_handles_far_apart_entities() {
+        const FAR: i32 = 1_000_000_000;
+        let mut sim = TestScenario::empty_world(15)
+            .with_creature(-FAR, -FAR, 100)
+            .with_structure(FAR, -FAR)
+            .with_structure(FAR, FAR)
+            .build();
+        sim.unregister_system(CreatureDegradationSystem::ID);
+        sim.register_system(Box::new(NeighbourWakeSystem::new(2_000_000_000)));
+        for id in 2..=3 {
+            sim.process_command(Command::SleepEntity(EntityId::new(id)))
+                .unwrap();
+        }
+        sim.process_command(Command::Tick).unwrap();
+
+        sim.process_command(Command::SetEntityProperty(SetEntityPropertyCmd {
+            entity_id: EntityId::new(1),
+            property: "name".to_string(),
+            value: PropertyValue::String("Rex".to_string()),
+        }))
+        .unwrap();
+        sim.process_command(Command::Tick).unwrap();
+
+        // 2e9 away is within the radius, 4e9 away is not
+        let world = sim.world().unwrap();
+        assert!(world.get_entity(EntityId::new(2)).unwrap().is_active());
+        assert!(world.get_entity(EntityId::new(3)).unwrap().is_dormant());
+    }
+
+    /// Takes one unit from resource 1 at tick `at`, as depletion would.
+    struct DepleteAt {
+        at: Tick,
+    }
+
+    impl ITickSystem for DepleteAt {
+        fn id(&self) -> SystemId {
+            "deplete_at"
+        }
+
+        fn order(&self) -> i32 {
+            sy_core::systems::order::RESOURCE_DEPLETION
+        }
+
+        fn run(&self, world: &mut World, ctx: &mut TickContext<'_>) {
+            let id = EntityId::new(1);
+            let Some(entity) = world.entities.get_mut(&id) else {
+                return;
+            };
+            if ctx.tick != self.at || !entity.is_active() {
+                return;
+            }
+            let remaining = entity.properties.amount.unwrap_or(1) - 1;
+            entity.properties.amount = Some(remaining);
+            ctx.emit(EventData::ResourceDepleted {
+                entity_id: id,
+                amount: 1,
+                remaining,
+            });
+        }
+    }
+
+    #[test]
+    fn depletion_on_the_idle_tick_keeps_the_entity_awake() {
+        let mut sim = TestScenario::empty_world(12)
+            .with_resource(0, 0, 100)
+            .with_structure(50, 0)
+            .build();
+        sim.unregister_system(ResourceDepletionSystem::ID);
+        sim.register_system(Box::new(DepleteAt { at: Tick(10) }));
+        sim.register_system(Box::new(IdleDormancySystem::new(10)));
+        sim.process_command(Command::SaveWorld).unwrap();
+
+        // Tick 10 is when both fall idle, and when the resource depletes
+        let events = sim.process_command(Command::TickN(10)).unwrap();
+        assert!(!events.iter().any(|e| matches!(
+            e.data,
+            EventData::EntityStateChanged {
+                entity_id,
+                new_state: EntityState::Dormant,
+                ..
+            } if entity_id == EntityId::new(1)
+        )));
+        let world = sim.world().unwrap();
+        assert!(world.get_entity(EntityId::new(1)).unwrap().is_active());
+        assert_eq!(
+            world.get_entity(EntityId::new(1)).unwrap().last_changed,
+            Tick(10)
+        );
+        assert!(world.get_entity(EntityId::new(2)).unwrap().is_dormant());
+
+        // It sleeps `idle_ticks` after that change
+        sim.process_command(Command::TickN(9)).unwrap();
+        assert!(sim
+            .world()
+            .unwrap()
+            .get_entity(EntityId::new(1))
+            .unwrap()
+            .is_active());
+        sim.process_command(Command::Tick).unwrap();
+        assert!(sim
+            .world()
+            .unwrap()
+            .get_entity(EntityId::new(1))
+            .unwrap()
+            .is_dormant());
+
+        let mut hasher = XxHasher::new();
+        let live = compute_canonical_hash(sim.world().unwrap(), &mut hasher);
+        let recovered = compute_canonical_hash(&recover(&sim), &mut hasher);
+        assert_eq!(live, recovered);
+    }
+
+    #[test]
+    fn set_entity_property_survives_crash_recovery() {
+        let mut sim = TestScenario::empty_world(9).with_creature(0, 0, 40).build();
+        sim.process_command(Command::SaveWorld).unwrap();
+
+        let id = EntityId::new(1);
+        let events = sim
+            .process_command(Command::SetEntityProperty(SetEntityPropertyCmd {
+                entity_id: id,
+                property: "name".to_string(),
+                value: PropertyValue::String("Rex".to_string()),
+            }))
+            .unwrap();
+        match &events[0].data {
+            EventData::EntityPropertyChanged {
+                old_value,
+                new_value,
+                ..
+            } => {
+                assert_eq!(*old_value, PropertyValue::String("Creature".to_string()));
+                assert_eq!(*new_value, PropertyValue::String("Rex".to_string()));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        sim.process_command(Command::SetEntityProperty(SetEntityPropertyCmd {
+            entity_id: id,
+            property: "amount".to_string(),
+            value: PropertyValue::UInt(7),
+        }))
+        .unwrap();
+
+        // Wrong type is rejected before touching the world
+        assert!(sim
+            .process_command(Command::SetEntityProperty(SetEntityPropertyCmd {
+                entity_id: id,
+                property: "health".to_string(),
+                value: PropertyValue::Bool(true),
+            }))
+            .is_err());
+
+        // So is any change to a dead entity (killed in memory only: the
+        // recovered world below never sees it die)
+        sim.world_mut().unwrap().mark_dead(id);
+        assert!(sim
+            .process_command(Command::SetEntityProperty(SetEntityPropertyCmd {
+                entity_id: id,
+                property: "name".to_string(),
+                value: PropertyValue::String("Ghost".to_string()),
+            }))
+            .is_err());
+        assert_eq!(
+            sim.world()
+                .unwrap()
+                .get_entity(id)
+                .unwrap()
+                .properties
+                .name
+                .as_deref(),
+            Some("Rex")
+        );
+
+        let recovered = recover(&sim);
+        let entity = recovered.get_entity(id).unwrap();
+        assert_eq!(entity.properties.name.as_deref(), Some("Rex"));
+        assert_eq!(entity.properties.amount, Some(7));
+    }
+
+    #[test]
+    fn timers_fire_and_survive_crash_recovery() {
+        let mut sim = TestScenario::empty_world(13)
+            .with_structure(0, 0)
+            .with_structure(1, 0)
+            .build();
+        let schedule = |at: u64, repeat_every, command| {
+            Command::ScheduleCommand(ScheduleCmd {
+                at: Tick(at),
+                repeat_every,
+                command: Box::new(command),
+            })
+        };
+
+        sim.process_command(schedule(5, None, Command::DespawnEntity(EntityId::new(1))))
+            .unwrap();
+        sim.process_command(schedule(3, Some(4), Command::SleepEntity(EntityId::new(2))))
+            .unwrap();
+        sim.process_command(schedule(5, Some(4), Command::WakeEntity(EntityId::new(2))))
+            .unwrap();
+
+        // Scheduling in the past is refused
+        assert!(sim
+            .process_command(schedule(0, None, Command::Tick))
+            .is_err());
+
+        sim.process_command(Command::TickN(4)).unwrap();
+        sim.process_command(Command::SaveWorld).unwrap();
+        assert!(sim
+            .world()
+            .unwrap()
+            .get_entity(EntityId::new(2))
+            .unwrap()
+            .is_dormant());
+
+        sim.process_command(Command::TickN(4)).unwrap();
+
+        let world = sim.world().unwrap();
+        assert!(world.get_entity(EntityId::new(1)).is_none());
+        assert!(world.get_entity(EntityId::new(2)).unwrap().is_dormant());
+        assert_eq!(world.timers.len(), 2);
+
+        let mut hasher = XxHasher::new();
+        let live = compute_canonical_hash(world, &mut hasher);
+        let recovered = compute_canonical_hash(&recover(&sim), &mut hasher);
+        assert_eq!(live, recovered);
+    }
+
+    #[test]
+    fn failing_timer_commands_leave_no_trace_and_survive_crash_recovery() {
+        let mut sim = TestScenario::empty_world(17)
+            .with_creature(0, 0, 40)
+            .with_creature(1, 0, 40)
+            .build();
+        sim.process_command(Command::SaveWorld).unwrap();
+        let schedule = |at: u64, command| {
+            Command::ScheduleCommand(ScheduleCmd {
+                at: Tick(at),
+                repeat_every: None,
+                command: Box::new(command),
+            })
+        };
+        let rename = |id, name: &str| {
+            Command::SetEntityProperty(SetEntityPropertyCmd {
+                entity_id: EntityId::new(id),
+                property: "name".to_string(),
+                value: PropertyValue::String(name.to_string()),
+            })
+        };
+
+        sim.process_command(schedule(2, Command::DespawnEntity(EntityId::new(1))))
+            .unwrap();
+        sim.process_command(schedule(3, rename(1, "Ghost")))
+            .unwrap();
+        sim.process_command(schedule(
+            3,
+            Command::Batch(vec![rename(2, "Rex"), rename(1, "Ghost")]),
+        ))
+        .unwrap();
+
+        let events = sim.process_command(Command::TickN(4)).unwrap();
+
+        // The ticks still complete, and neither timer left anything behind
+        let processed = events
+            .iter()
+            .filter(|e| matches!(e.data, EventData::TickProcessed { .. }))
+            .count();
+        assert_eq!(processed, 4);
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e.data, EventData::EntityPropertyChanged { .. })));
+
+        let world = sim.world().unwrap();
+        assert_eq!(world.current_tick, Tick(4));
+        assert!(world.timers.is_empty());
+        assert!(world.get_entity(EntityId::new(1)).is_none());
+        let survivor = world.get_entity(EntityId::new(2)).unwrap();
+        assert_eq!(survivor.properties.name.as_deref(), Some("Creature"));
+
+        let mut hasher = XxHasher::new();
+        let live = compute_canonical_hash(world, &mut hasher);
+        let recovered = compute_canonical_hash(&recover(&sim), &mut hasher);
+        assert_eq!(live, recovered);
+    }
+
+    #[test]
+    fn inputs_apply_in_order_and_survive_crash_recovery() {
+        let mut sim = TestScenario::empty_world(19).build();
+        let input = |source, seq, at: u64, x| {
+            Command::QueueInput(QueueInputCmd {
+                source: SourceId::new(source),
+                seq,
+                at: Tick(at),
+                command: Box::new(Command::SpawnEntity(SpawnEntityCmd {
+                    position: WorldPos::new(ZoneId::ORIGIN, Position::new(x, 0, 0)),
+                    kind: EntityKind::Item,
+                    properties: EntityProperties::default(),
+                    components: Components::default(),
+                    expires_at: None,
+                })),
+            })
+        };
+
+        // Arrival order differs from application order
+        sim.process_command(input(2, 1, 3, 21)).unwrap();
+        sim.process_command(input(1, 4, 3, 18)).unwrap();
+        sim.process_command(input(1, 8, 5, 14)).unwrap();
+        sim.process_command(Command::SaveWorld).unwrap();
+
+        // Duplicate, stale and late inputs are refused
+        assert!(sim.process_command(input(1, 8, 4, 0)).is_err());
+        assert!(sim.process_command(input(1, 7, 4, 0)).is_err());
+        assert!(sim.process_command(input(3, 1, 0, 0)).is_err());
+
+        sim.process_command(Command::TickN(5)).unwrap();
+
+        let world = sim.world().unwrap();
+        assert!(world.inputs.is_empty());
+        let xs: Vec<i32> = (1..=3)
+            .map(|id| world.get_entity(EntityId::new(id)).unwrap().position.pos.x)
+            .collect();
+        assert_eq!(xs, vec![18, 21, 14]);
+
+        let mut hasher = XxHasher::new();
+        let live = compute_canonical_hash(world, &mut hasher);
+        let recovered = compute_canonical_hash(&recover(&sim), &mut hasher);
+        assert_eq!(live, recovered);
+    }
+
+    #[test]
+    fn attached_entities_follow_parents_and_survive_crash_recovery() {
+        let mut sim = TestScenario::empty_world(23)
+            .with_entity(SpawnEntityCmd {
+                position: WorldPos::origin(),
+                kind: EntityKind::Creature,
+                properties: EntityProperties::default(),
+                components: Components::new()
+                    .with(&Velocity {
+                        dx: 1,
+                        dy: 0,
+                        dz: 0,
+                    })
+                    .unwrap(),
+                expires_at: None,
+            })
+            .with_structure(5, 5)
+            .with_structure(50, 50)
+            .with_structure(60, 60)
+            .build();
+        let attach = |entity_id, parent_id, on_parent_despawn| {
+            Command::AttachEntity(AttachEntityCmd {
+                entity_id: EntityId::new(entity_id),
+                parent_id: EntityId::new(parent_id),
+                on_parent_despawn,
+            })
+        };
+
+        // A creature carries a crate; a tower carries a turret
+        sim.process_command(attach(2, 1, OnParentDespawn::Orphan))
+            .unwrap();
+        sim.process_command(attach(4, 3, OnParentDespawn::Cascade))
+            .unwrap();
+        assert!(sim
+            .process_command(attach(1, 2, OnParentDespawn::Orphan))
+            .is_err());
+        assert!(sim
+            .process_command(Command::MoveEntity(MoveEntityCmd {
+                entity_id: EntityId::new(4),
+                to: WorldPos::origin(),
+            }))
+            .is_err());
+        sim.process_command(Command::SaveWorld).unwrap();
+
+        sim.process_command(Command::TickN(3)).unwrap();
+        let carried = WorldPos::new(ZoneId::ORIGIN, Position::new(3, 0, 0));
+        assert_eq!(
+            sim.world()
+                .unwrap()
+                .get_entity(EntityId::new(2))
+                .unwrap()
+                .position,
+            carried
+        );
+
+        let events = sim
+            .process_command(Command::DespawnEntity(EntityId::new(1)))
+            .unwrap();
+        assert!(matches!(
+            events[0].data,
+            EventData::EntityDetached { entity_id, .. } if entity_id == EntityId::new(2)
+        ));
+        let events = sim
+            .process_command(Command::DespawnEntity(EntityId::new(3)))
+            .unwrap();
+        assert!(matches!(
+            events[0].data,
+            EventData::EntityDespawned {
+                reason: DespawnReason::ParentDespawned,
+                ..
+            }
+        ));
+        sim.process_command(Command::Tick).unwrap();
+
+        let world = sim.world().unwrap();
+        let dropped = world.get_entity(EntityId::new(2)).unwrap();
+        assert_eq!(dropped.position, carried);
+        assert!(dropped.attachment.is_none());
+        assert!(world.get_entity(EntityId::new(4)).is_none());
+
+        let mut hasher = XxHasher::new();
+        let live = compute_canonical_hash(world, &mut hasher);
+        let recovered = compute_canonical_hash(&recover(&sim), &mut hasher);
+        assert_eq!(live, recovered);
+    }
+
+    /// Moves entity 1 at tick `at` without going through `World`, leaving
+    /// the zone indexes and its attached children behind.
+    struct CorruptingSystem {
+        at: Tick,
+    }
+
+    impl ITickSystem for CorruptingSystem {
+        fn id(&self) -> SystemId {
+            "corrupting"
+        }
+
+        fn order(&self) -> i32 {
+            0
+        }
+
+        fn run(&self, world: &mut World, ctx: &mut TickContext<'_>) {
+            if ctx.tick == self.at {
+                if let Some(entity) = world.entities.get_mut(&EntityId::new(1)) {
+                    entity.position.pos = Position::new(99, 0, 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn strict_mode_checks_ticks_and_recovery() {
+        let mut sim = TestScenario::empty_world(31)
+            .with_resource(0, 0, 100)
+            .with_creature(5, 5, 100)
+            .build();
+        sim.set_strict(true);
+        sim.process_command(Command::AttachEntity(AttachEntityCmd {
+            entity_id: EntityId::new(2),
+            parent_id: EntityId::new(1),
+            on_parent_despawn: OnParentDespawn::Cascade,
+        }))
+        .unwrap();
+        sim.process_command(Command::SetEntityExpiry(SetEntityExpiryCmd {
+            entity_id: EntityId::new(2),
+            expires_at: Some(Tick(50)),
+        }))
+        .unwrap();
+        sim.process_command(Command::TickN(5)).unwrap();
+        sim.process_command(Command::SaveWorld).unwrap();
+        sim.process_command(Command::TickN(5)).unwrap();
+
+        let world_id = sim.world().unwrap().id().to_string();
+        sim.process_command(Command::LoadWorld(LoadWorldCmd { world_id }))
+            .unwrap();
+        assert_eq!(sim.current_tick(), Tick(10));
+
+        // A rule bypassing World's bookkeeping is caught at the faulty tick
+        sim.register_system(Box::new(CorruptingSystem { at: Tick(12) }));
+        let logged = sim.event_log().len();
+        let mut hasher = XxHasher::new();
+        let before = compute_canonical_hash(sim.world().unwrap(), &mut hasher);
+        let error = sim.process_command(Command::TickN(5)).unwrap_err();
+
+        let ApiError::InvariantViolated { tick, violations } = error else {
+            panic!("unexpected error: {}", error);
+        };
+        assert_eq!(tick, Tick(12));
+        assert!(violations.iter().any(|v| v.starts_with("[spatial_index]")));
+        assert!(violations
+            .iter()
+            .any(|v| v.starts_with("[hierarchy]") && v.contains("E2")));
+        assert_eq!(sim.event_log().len(), logged);
+
+        // Nothing of the command stays in memory, not even its good tick
+        assert_eq!(sim.current_tick(), Tick(10));
+        assert_eq!(
+            compute_canonical_hash(sim.world().unwrap(), &mut hasher),
+            before
+        );
+    }
+
+    #[test]
+    fn archetype_spawns_survive_template_change_and_recovery() {
+        let wolf = |health| Archetype {
+            name: "wolf".to_string(),
+            kind: EntityKind::Creature,
+            properties: EntityProperties {
+                name: Some("Wolf".to_string()),
+                health: Some(health),
+                ..Default::default()
+            },
+            components: Components::default(),
+            tags: ["hostile".to_string()].into(),
+        };
+        let mut sim = TestScenario::empty_world(29)
+            .with_archetype(wolf(80))
+            .with_archetype_entity("wolf", 0, 0)
+            .build();
+        sim.process_command(Command::SaveWorld).unwrap();
+
+        // Redefining the template only affects later spawns
+        sim.process_command(Command::DefineArchetype(wolf(40)))
+            .unwrap();
+        sim.process_command(Command::SpawnFromArchetype(SpawnFromArchetypeCmd {
+            archetype: "wolf".to_string(),
+            position: WorldPos::origin(),
+            properties: EntityProperties {
+                name: Some("Alpha".to_string()),
+                ..Default::default()
+            },
+            expires_at: None,
+        }))
+        .unwrap();
+        assert!(matches!(
+            sim.process_command(Command::SpawnFromArchetype(SpawnFromArchetypeCmd {
+                archetype: "bear".to_string(),
+                position: WorldPos::origin(),
+                properties: EntityProperties::default(),
+                expires_at: None,
+            })),
+            Err(ApiError::ArchetypeNotFound(_))
+        ));
+
+        let world = sim.world().unwrap();
+        let first = world.get_entity(EntityId::new(1)).unwrap();
+        let second = world.get_entity(EntityId::new(2)).unwrap();
+        assert_eq!(first.kind, EntityKind::Creature);
+        assert_eq!(first.properties.health, Some(80));
+        assert_eq!(second.properties.health, Some(40));
+        assert_eq!(second.properties.name.as_deref(), Some("Alpha"));
+        assert!(second.components.get::<Tags>().unwrap().contains("hostile"));
+
+        let mut hasher = XxHasher::new();
+        let live = compute_canonical_hash(world, &mut hasher);
+        let recovered = compute_canonical_hash(&recover(&sim), &mut hasher);
+        assert_eq!(live, recovered);
+    }
+
+    #[test]
+    fn expired_entities_despawn_and_survive_crash_recovery() {
+        let mut sim = TestScenario::empty_world(17).with_structure(0, 0).build();
+        sim.process_command(Command::CreateZone(CreateZoneCmd {
+            zone_id: ZoneId::new(1),
+            name: None,
+        }))
+        .unwrap();
+        for (zone, expires_at) in [(ZoneId::ORIGIN, 3), (ZoneId::new(1), 3)] {
+            sim.process_command(Command::SpawnEntity(SpawnEntityCmd {
+                position: WorldPos::new(zone, Position::ORIGIN),
+                kind: EntityKind::Item,
+                properties: EntityProperties::default(),
+                components: Components::default(),
+                expires_at: Some(Tick(expires_at)),
+            }))
+            .unwrap();
+        }
+        sim.process_command(Command::SetEntityExpiry(SetEntityExpiryCmd {
+            entity_id: EntityId::new(1),
+            expires_at: Some(Tick(5)),
+        }))
+        .unwrap();
+        sim.process_command(Command::UnloadZone(ZoneId::new(1)))
+            .unwrap();
+        sim.process_command(Command::SaveWorld).unwrap();
+
+        // Expiry in the past is refused
+        assert!(sim
+            .process_command(Command::SetEntityExpiry(SetEntityExpiryCmd {
+                entity_id: EntityId::new(1),
+                expires_at: Some(Tick(0)),
+            }))
+            .is_err());
+
+        let events = sim.process_command(Command::TickN(6)).unwrap();
+        let expired: Vec<(Tick, EntityId)> = events
+            .iter()
+            .filter_map(|e| match e.data {
+                EventData::EntityDespawned {
+                    entity_id,
+                    reason: DespawnReason::Expired,
+                } => Some((e.tick, entity_id)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            expired,
+            vec![(Tick(3), EntityId::new(2)), (Tick(5), EntityId::new(1))]
+        );
+
+        // Frozen until its zone is loaded again
+        assert!(sim.world().unwrap().get_entity(EntityId::new(3)).is_some());
+        sim.process_command(Command::LoadZone(ZoneId::new(1)))
+            .unwrap();
+        sim.process_command(Command::Tick).unwrap();
+        assert_eq!(sim.world().unwrap().entity_count(), 0);
+
+        let mut hasher = XxHasher::new();
+        let live = compute_canonical_hash(sim.world().unwrap(), &mut hasher);
+        let recovered = recover(&sim);
+        assert_eq!(live, compute_canonical_hash(&recovered, &mut hasher));
+        assert!(recovered.expired_entities(Tick(u64::MAX)).is_empty());
+    }
+
+    #[test]
+    fn spatial_queries_match_after_crash_recovery() {
+        let mut sim = TestScenario::empty_world(19)
+            .with_creature(0, 0, 100)
+            .with_creature(3, 0, 100)
+            .with_resource(8, 8, 50)
+            .with_structure(60, 60)
+            .build();
+        sim.process_command(Command::SaveWorld).unwrap();
+        sim.process_command(Command::MoveEntity(MoveEntityCmd {
+            entity_id: EntityId::new(2),
+            to: WorldPos::new(ZoneId::ORIGIN, Position::new(30, 0, 0)),
+        }))
+        .unwrap();
+        sim.process_command(Command::DespawnEntity(EntityId::new(4)))
+            .unwrap();
+
+        let live = sim.world().unwrap();
+        let recovered = recover(&sim);
+        for world in [live, &recovered] {
+            assert_eq!(
+                world.entities_within(ZoneId::ORIGIN, Position::ORIGIN, 12),
+                vec![EntityId::new(1), EntityId::new(3)]
+            );
+            assert_eq!(
+                world.nearest_of_kind(
+                    ZoneId::ORIGIN,
+                    Position::new(40, 0, 0),
+                    EntityKind::Creature,
+                    1
+                ),
+                vec![EntityId::new(2)]
+            );
+            assert!(world
+                .entities_in_box(
+                    ZoneId::ORIGIN,
+                    Position::new(50, 50, 0),
+                    Position::new(70, 70, 0)
+                )
+                .is_empty());
+        }
+    }
+
+    #[test]
+    fn state_at_matches_the_live_world_at_each_tick() {
+        let mut sim = TestScenario::empty_world(29)
+            .with_creature(0, 0, 100)
+            .with_resource(4, 4, 30)
+            .build();
+        let mut hasher = XxHasher::new();
+        let mut seen = Vec::new();
+        let mut record = |sim: &Simulation<_, _, _, _>| {
+            let world = sim.world().unwrap();
+            seen.push((
+                world.current_tick,
+                compute_canonical_hash(world, &mut hasher),
+            ));
+        };
+
+        record(&sim);
+        sim.process_command(Command::TickN(10)).unwrap();
+        sim.process_command(Command::MoveEntity(MoveEntityCmd {
+            entity_id: EntityId::new(1),
+            to: WorldPos::new(ZoneId::ORIGIN, Position::new(9, 0, 0)),
+        }))
+        .unwrap();
+        record(&sim);
+        sim.process_command(Command::SaveWorld).unwrap();
+        sim.process_command(Command::TickN(15)).unwrap();
+        sim.process_command(Command::DespawnEntity(EntityId::new(2)))
+            .unwrap();
+        // End of tick: includes commands processed at that tick
+        record(&sim);
+        sim.process_command(Command::TickN(5)).unwrap();
+        record(&sim);
+
+        let world_id = sim.world().unwrap().id().to_string();
+        let snapshot = World::from_bytes(&sim.store().load_snapshot(&world_id).unwrap()).unwrap();
+        assert_eq!(snapshot.meta.snapshot_tick, Tick(10));
+        // Saving keeps the root of the RNG streams: the seed
+        assert_eq!(snapshot.rng_state, 29);
+        let events = sim.event_log().read_all_valid().unwrap();
+
+        // Before the snapshot from genesis, after it from the snapshot
+        for (tick, hash) in seen {
+            let past = state_at(Some(snapshot.clone()), &events, tick).unwrap();
+            assert_eq!(past.current_tick, tick);
+            assert_eq!(compute_canonical_hash(&past, &mut hasher), hash, "{}", tick);
+        }
+        assert!(state_at(Some(snapshot), &events, Tick(31)).is_err());
+    }
+
+    #[test]
+    fn fork_continues_like_its_parent_then_diverges() {
+        let mut parent = TestScenario::empty_world(31)
+            .with_creature(0, 0, 100)
+            .with_resource(4, 4, 30)
+            .build();
+        let parent_id = parent.world().unwrap().id().to_string();
+        let mut hasher = XxHasher::new();
+
+        parent.process_command(Command::TickN(10)).unwrap();
+        parent.process_command(Command::SaveWorld).unwrap();
+        parent.process_command(Command::TickN(10)).unwrap();
+        let hash_20 = compute_canonical_hash(parent.world().unwrap(), &mut hasher);
+        let fork_event = parent.event_log().last_event_id();
+        parent.process_command(Command::TickN(5)).unwrap();
+        let hash_25 = compute_canonical_hash(parent.world().unwrap(), &mut hasher);
+
+        // Copy the parent's storage, as server_d copies its files
+        let mut store = MockWorldStore::new();
+        let meta = parent.store().load_meta(&parent_id).unwrap();
+        store.save_meta(&meta).unwrap();
+        let snapshot = parent.store().load_snapshot(&parent_id).unwrap();
+        store.save_snapshot(&parent_id, &snapshot).unwrap();
+        let mut log = MockEventLog::new();
+        log.append_batch(parent.event_log().read_all_valid().unwrap())
+            .unwrap();
+
+        let meta = sy_tools::fork::fork_world(
+            &mut store,
+            &mut log,
+            &parent_id,
+            "what_if",
+            ForkAt::Tick(Tick(20)),
+            Some("What if".to_string()),
+        )
+        .unwrap();
+        let point = meta.forked_from.unwrap();
+        assert_eq!((point.tick, point.event_id), (Tick(20), fork_event));
+        assert_eq!(point.parent_world_id, parent_id);
+        assert_eq!(log.last_event_id(), fork_event);
+
+        let mut fork =
+            Simulation::new(MockRng::new(RngSeed::new(31)), MockClock::new(), log, store);
+        fork.process_command(Command::LoadWorld(LoadWorldCmd {
+            world_id: "what_if".to_string(),
+        }))
+        .unwrap();
+        let world = fork.world().unwrap();
+        assert_eq!(
+            (world.id(), world.meta.name.as_str()),
+            ("what_if", "What if")
+        );
+        assert_eq!(compute_canonical_hash(world, &mut hasher), hash_20);
+        assert_eq!(
+            fork.event_log().last_event_id(),
+            EventId::new(fork_event.as_u64() + 1)
+        );
+
+        // Same history as the parent until something differs
+        fork.process_command(Command::TickN(5)).unwrap();
+        assert_eq!(
+            compute_canonical_hash(fork.world().unwrap(), &mut hasher),
+            hash_25
+        );
+        fork.process_command(Command::DespawnEntity(EntityId::new(1)))
+            .unwrap();
+        fork.process_command(Command::SaveWorld).unwrap();
+        assert!(fork.world().unwrap().get_entity(EntityId::new(1)).is_none());
+        assert!(parent
+            .world()
+            .unwrap()
+            .get_entity(EntityId::new(1))
+            .is_some());
+        assert!(fork
+            .store()
+            .load_meta("what_if")
+            .unwrap()
+            .forked_from
+            .is_some());
+    }
+
+    #[test]
+    fn portal_transfers_entity_and_survives_crash_recovery() {
+        let mut sim = TestScenario::empty_world(23)
+            .with_entity(SpawnEntityCmd {
+                position: WorldPos::origin(),
+                kind: EntityKind::Creature,
+                properties: EntityProperties::default(),
+                components: Components::new()
+                    .with(&Velocity {
+                        dx: 1,
+                        dy: 0,
+                        dz: 0,
+                    })
+                    .unwrap(),
+                expires_at: None,
+            })
+            .build();
+        sim.process_command(Command::CreateZone(CreateZoneCmd {
+            zone_id: ZoneId::new(1),
+            name: None,
+        }))
+        .unwrap();
+        sim.process_command(Command::SaveWorld).unwrap();
+
+        let portal = Portal {
+            from: Position::new(3, 0, 0),
+            to: WorldPos::new(ZoneId::new(1), Position::new(0, 100, 0)),
+        };
+        let events = sim
+            .process_command(Command::AddPortal(AddPortalCmd {
+                zone_id: ZoneId::ORIGIN,
+                portal,
+            }))
+            .unwrap();
+        assert!(matches!(events[0].data, EventData::PortalAdded { .. }));
+
+        let events = sim.process_command(Command::TickN(4)).unwrap();
+        let transfers: Vec<(WorldPos, WorldPos)> = events
+            .iter()
+            .filter_map(|e| match e.data {
+                EventData::EntityMoved { from, to, .. } if from.zone != to.zone => Some((from, to)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            transfers,
+            vec![(
+                WorldPos::new(ZoneId::ORIGIN, Position::new(2, 0, 0)),
+                portal.to
+            )]
+        );
+
+        let world = sim.world().unwrap();
+        let entity = world.get_entity(EntityId::new(1)).unwrap();
+        assert_eq!(
+            entity.position,
+            WorldPos::new(ZoneId::new(1), Position::new(1, 100, 0))
+        );
+
+        let recovered = recover(&sim);
+        assert_eq!(recovered.zones[&ZoneId::ORIGIN].portals, vec![portal]);
+        assert_eq!(
+            recovered.zones[&ZoneId::new(1)].entities,
+            vec![EntityId::new(1)]
+        );
+        let mut hasher = XxHasher::new();
+        assert_eq!(
+            compute_canonical_hash(world, &mut hasher),
+            compute_canonical_hash(&recovered, &mut hasher)
+        );
+    }
+
+    #[test]
+    fn fast_movers_use_portals_they_step_over() {
+        let mut sim = TestScenario::empty_world(24)
+            .with_entity(SpawnEntityCmd {
+                position: WorldPos::origin(),
+                kind: EntityKind::Creature,
+                properties: EntityProperties::default(),
+                components: Components::new()
+                    .with(&MoveTarget {
+                        target: Position::new(10, 0, 0),
+                        speed: 2,
+                    })
+                    .unwrap(),
+                expires_at: None,
+            })
+            .build();
+        sim.process_command(Command::CreateZone(CreateZoneCmd {
+            zone_id: ZoneId::new(1),
+            name: None,
+        }))
+        .unwrap();
+        sim.process_command(Command::SaveWorld).unwrap();
+
+        // Steps land on x = 2, 4, ...: the entrance at x = 3 is stepped over
+        let portal = Portal {
+            from: Position::new(3, 0, 0),
+            to: WorldPos::new(ZoneId::new(1), Position::new(0, 100, 0)),
+        };
+        sim.process_command(Command::AddPortal(AddPortalCmd {
+            zone_id: ZoneId::ORIGIN,
+            portal,
+        }))
+        .unwrap();
+
+        let events = sim.process_command(Command::TickN(2)).unwrap();
+        let moves: Vec<(WorldPos, WorldPos)> = events
+            .iter()
+            .filter_map(|e| match e.data {
+                EventData::EntityMoved { from, to, .. } => Some((from, to)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            moves,
+            vec![
+                (
+                    WorldPos::origin(),
+                    WorldPos::new(ZoneId::ORIGIN, Position::new(2, 0, 0))
+                ),
+                (
+                    WorldPos::new(ZoneId::ORIGIN, Position::new(2, 0, 0)),
+                    portal.to
+                ),
+            ]
+        );
+
+        let mut hasher = XxHasher::new();
+        assert_eq!(
+            compute_canonical_hash(sim.world().unwrap(), &mut hasher),
+            compute_canonical_hash(&recover(&sim), &mut hasher)
+        );
+    }
+
+    #[test]
+    fn adding_entities_does_not_shift_other_streams() {
+        let base = || {
+            (0..5).fold(TestScenario::empty_world(29), |s, i| {
+                s.with_resource(i * 3, 0, 1000)
+            })
+        };
+        let mut plain = base().build();
+        let mut crowded = base()
+            .with_creature(1, 1, 100)
+            .with_resource(50, 50, 1000)
+            .build();
+
+        plain.process_command(Command::TickN(500)).unwrap();
+        crowded.process_command(Command::TickN(500)).unwrap();
+
+        for id in 1..=5 {
+            let amount = |sim: &Simulation<_, _, _, _>| {
+                sim.world()
+                    .unwrap()
+                    .get_entity(EntityId::new(id))
+                    .unwrap()
+                    .properties
+                    .amount
+            };
+            assert_eq!(amount(&plain), amount(&crowded));
+            assert!(amount(&plain) < Some(1000));
+        }
+    }
+
+    #[test]
+    fn subscribers_receive_persisted_events_by_zone_and_type() {
+        let mut sim = TestScenario::empty_world(37)
+            .with_creature(0, 0, 100)
+            .build();
+        sim.process_command(Command::CreateZone(CreateZoneCmd {
+            zone_id: ZoneId::new(1),
+            name: None,
+        }))
+        .unwrap();
+
+        let zone_events = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&zone_events);
+        sim.subscribe(
+            EventFilter::all().with_zone(ZoneId::new(1)),
+            Box::new(move |event: &SimEvent| log.lock().unwrap().push(event.clone())),
+        );
+        let ticks = Arc::new(Mutex::new(0));
+        let count = Arc::clone(&ticks);
+        let tick_subscription = sim.subscribe(
+            EventFilter::all().with_type("TickProcessed"),
+            Box::new(move |_: &SimEvent| *count.lock().unwrap() += 1),
+        );
+
+        for (kind, amount) in [
+            (EntityKind::Resource, Some(1)),
+            (EntityKind::Structure, None),
+        ] {
+            sim.process_command(Command::SpawnEntity(SpawnEntityCmd {
+                position: WorldPos::new(ZoneId::new(1), Position::ORIGIN),
+                kind,
+                properties: EntityProperties {
+                    name: None,
+                    amount,
+                    health: None,
+                },
+                components: Components::default(),
+                expires_at: None,
+            }))
+            .unwrap();
+        }
+
+        // Nothing from a command that failed
+        let error = sim.process_command(Command::Batch(vec![
+            Command::TickN(5),
+            Command::DespawnEntity(EntityId::new(99)),
+        ]));
+        assert!(error.is_err());
+        assert_eq!(zone_events.lock().unwrap().len(), 2);
+
+        // Entities despawned before delivery are still matched by zone
+        sim.process_command(Command::DespawnEntity(EntityId::new(3)))
+            .unwrap();
+        sim.process_command(Command::TickN(1000)).unwrap();
+        assert!(sim.world().unwrap().get_entity(EntityId::new(2)).is_none());
+        assert!(sim.unsubscribe(tick_subscription));
+        sim.process_command(Command::Tick).unwrap();
+        assert_eq!(*ticks.lock().unwrap(), 1000);
+
+        let zone_events = zone_events.lock().unwrap();
+        let types: Vec<&str> = zone_events.iter().map(|e| e.data.type_name()).collect();
+        assert_eq!(
+            types,
+            vec![
+                "EntitySpawned",
+                "EntitySpawned",
+                "EntityDespawned",
+                "ResourceDepleted",
+                "EntityStateChanged",
+                "EntityDespawned",
+            ]
+        );
+        assert!(zone_events
+            .windows(2)
+            .all(|w| w[0].event_id < w[1].event_id));
+
+        // Exactly the events of the log, with their IDs
+        let logged = sim.event_log().read_all_valid().unwrap();
+        for event in zone_events.iter() {
+            let same = logged
+                .iter()
+                .find(|e| e.event_id == event.event_id)
+                .unwrap();
+            assert_eq!(same.data.type_name(), event.data.type_name());
+        }
+    }
+
+    #[test]
+    fn batch_is_all_or_nothing() {
+        let mut sim = TestScenario::empty_world(31)
+            .with_creature(0, 0, 100)
+            .build();
+        let spawn = |x| {
+            Command::SpawnEntity(SpawnEntityCmd {
+                position: WorldPos::new(ZoneId::ORIGIN, Position::new(x, 0, 0)),
+                kind: EntityKind::Structure,
+                properties: EntityProperties::default(),
+                components: Components::default(),
+                expires_at: None,
+            })
+        };
+
+        let mut hasher = XxHasher::new();
+        let before = compute_canonical_hash(sim.world().unwrap(), &mut hasher);
+        let logged = sim.event_log().len();
+
+        let result = sim.process_command(Command::Batch(vec![
+            spawn(1),
+            Command::TickN(3),
+            Command::DespawnEntity(EntityId::new(99)),
+        ]));
+        match result {
+            Err(ApiError::BatchFailed { index, .. }) => assert_eq!(index, 2),
+            other => panic!("expected BatchFailed, got {:?}", other),
+        }
+        assert_eq!(
+            compute_canonical_hash(sim.world().unwrap(), &mut hasher),
+            before
+        );
+        assert_eq!(sim.event_log().len(), logged);
+        assert_eq!(sim.current_tick(), Tick::ZERO);
+
+        let batches = sim.event_log().batch_count();
+        let events = sim
+            .process_command(Command::Batch(vec![spawn(1), spawn(2), Command::Tick]))
+            .unwrap();
+        assert_eq!(sim.event_log().batch_count(), batches + 1);
+        assert!(events
+            .windows(2)
+            .all(|w| w[1].event_id.as_u64() == w[0].event_id.as_u64() + 1));
+        // Rolled-back IDs are reused: the failed batch never happened
+        assert!(sim.world().unwrap().get_entity(EntityId::new(2)).is_some());
+        assert!(sim.world().unwrap().get_entity(EntityId::new(3)).is_some());
+    }
+
+    #[test]
+    fn registered_system_runs_in_order() {
+        use sy_core::{ITickSystem, SystemId, TickContext};
+
+        struct Marker;
+
+        impl ITickSystem for Marker {
+            fn id(&self) -> SystemId {
+                "marker"
+            }
+
+            fn order(&self) -> i32 {
+                i32::MIN
+            }
+
+            fn run(&self, _world: &mut World, ctx: &mut TickContext<'_>) {
+                ctx.emit(EventData::ZoneLoaded {
+                    zone_id: ZoneId::ORIGIN,
+                });
+            }
+        }
+
+        let mut sim = TestScenario::empty_world(42).build();
+        sim.register_system(Box::new(Marker));
+        assert_eq!(sim.system_ids()[0], "marker");
+
+        let events = sim.process_command(Command::Tick).unwrap();
+        assert!(matches!(events[0].data, EventData::ZoneLoaded { .. }));
+
+        assert!(sim.unregister_system("marker"));
+        let events = sim.process_command(Command::Tick).unwrap();
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e.data, EventData::ZoneLoaded { .. })));
+    }
 }
@@ -2,5 +2,6 @@
 //!
 //! Operator utilities for inspection, replay, and debugging.
 
+pub mod fork;
 pub mod inspect;
 pub mod replay;
@@ -6,6 +6,33 @@
 //! - Verify determinism by replaying events
 //! - Compare state hashes at checkpoints
 //! - Debug divergence issues
+//! - Reconstruct a stored world at a past tick (incident analysis)
 
-// Replay functionality for future phases
-// Will use IEventLog to replay events and verify determinism
+use sy_core::ports::{IEventLog, IWorldStore};
+use sy_core::World;
+use sy_types::{SimError, SimResult, Tick};
+
+/// Rebuild a stored world as it was at the end of `tick`.
+///
+/// The store keeps the latest snapshot only: it is used when taken at or
+/// before `tick`, otherwise the whole log is replayed from genesis (see
+/// `sy_core::replay::state_at`). Nothing is written back.
+pub fn state_at(
+    store: &impl IWorldStore,
+    event_log: &impl IEventLog,
+    world_id: &str,
+    tick: Tick,
+) -> SimResult<World> {
+    if !store.exists(world_id) {
+        return Err(SimError::InvalidOperation(format!(
+            "World not found: {}",
+            world_id
+        )));
+    }
+
+    let snapshot = store.load_snapshot(world_id)?;
+    let snapshot = World::from_bytes(&snapshot).map_err(SimError::CorruptedState)?;
+    let events = event_log.read_all_valid()?;
+
+    sy_core::replay::state_at(Some(snapshot), &events, tick).map_err(SimError::InvalidOperation)
+}
@@ -178,13 +178,60 @@ impl std::fmt::Display for EventId {
     }
 }
 
+/// Unique identifier for a scheduled timer in a world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct TimerId(pub u64);
+
+impl TimerId {
+    #[inline]
+    pub fn new(id: u64) -> Self {
+        TimerId(id)
+    }
+
+    #[inline]
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for TimerId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TM{}", self.0)
+    }
+}
+
+/// Identifier of an external input source (a client, a tool, a script).
+/// Orders inputs queued for the same tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SourceId(pub u32);
+
+impl SourceId {
+    #[inline]
+    pub fn new(id: u32) -> Self {
+        SourceId(id)
+    }
+
+    #[inline]
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for SourceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SRC{}", self.0)
+    }
+}
+
 // ============================================================================
 // Spatial types
 // ============================================================================
 
 /// A position within a zone (local coordinates).
 /// Uses i32 to allow negative coordinates if needed.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default,
+)]
 pub struct Position {
     pub x: i32,
     pub y: i32,
@@ -199,10 +246,23 @@ impl Position {
         Position { x, y, z }
     }
 
-    /// Manhattan distance to another position
+    /// Manhattan distance to another position (no overflow even across
+    /// the whole i32 range)
     #[inline]
-    pub fn manhattan_distance(&self, other: &Position) -> u32 {
-        ((self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()) as u32
+    pub fn manhattan_distance(&self, other: &Position) -> u64 {
+        (self.x as i64 - other.x as i64).unsigned_abs()
+            + (self.y as i64 - other.y as i64).unsigned_abs()
+            + (self.z as i64 - other.z as i64).unsigned_abs()
+    }
+
+    /// Squared Euclidean distance to another position (exact, no floats,
+    /// no overflow even across the whole i32 range)
+    #[inline]
+    pub fn distance_squared(&self, other: &Position) -> u128 {
+        let dx = (self.x as i64 - other.x as i64).unsigned_abs() as u128;
+        let dy = (self.y as i64 - other.y as i64).unsigned_abs() as u128;
+        let dz = (self.z as i64 - other.z as i64).unsigned_abs() as u128;
+        dx * dx + dy * dy + dz * dz
     }
 }
 
@@ -212,6 +272,290 @@ impl std::fmt::Display for Position {
     }
 }
 
+// ============================================================================
+// Fixed-point numbers
+// ============================================================================
+
+/// Signed fixed-point number, Q32.32 (32 integer bits, 32 fractional bits).
+///
+/// Simulation code never uses `f32`/`f64`: float results may differ across
+/// compilers and targets. Every `Fixed` operation is plain integer arithmetic,
+/// identical everywhere. Arithmetic saturates at the representable range;
+/// multiplication and division round toward negative infinity.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default,
+)]
+#[serde(transparent)]
+pub struct Fixed(i64);
+
+impl Fixed {
+    /// Number of fractional bits
+    pub const FRAC_BITS: u32 = 32;
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(1 << Self::FRAC_BITS);
+    pub const HALF: Fixed = Fixed(1 << (Self::FRAC_BITS - 1));
+    pub const MIN: Fixed = Fixed(i64::MIN);
+    pub const MAX: Fixed = Fixed(i64::MAX);
+
+    /// Build from the raw Q32.32 representation.
+    #[inline]
+    pub const fn from_bits(bits: i64) -> Self {
+        Fixed(bits)
+    }
+
+    /// Raw Q32.32 representation (what gets serialized and hashed).
+    #[inline]
+    pub const fn to_bits(self) -> i64 {
+        self.0
+    }
+
+    #[inline]
+    pub const fn from_int(value: i32) -> Self {
+        Fixed((value as i64) << Self::FRAC_BITS)
+    }
+
+    /// `num / den`, rounded toward negative infinity. Panics if `den` is 0.
+    pub fn from_ratio(num: i64, den: i64) -> Self {
+        Fixed::div_bits(num, den)
+    }
+
+    /// Largest integer less than or equal to `self`.
+    #[inline]
+    pub const fn floor(self) -> i32 {
+        (self.0 >> Self::FRAC_BITS) as i32
+    }
+
+    /// Nearest integer (halves round up).
+    #[inline]
+    pub const fn round(self) -> i32 {
+        (self.0.saturating_add(Self::HALF.0) >> Self::FRAC_BITS) as i32
+    }
+
+    /// Fractional part, in [0, 1).
+    #[inline]
+    pub const fn fract(self) -> Fixed {
+        Fixed(self.0 & (Self::ONE.0 - 1))
+    }
+
+    #[inline]
+    pub const fn abs(self) -> Fixed {
+        Fixed(self.0.saturating_abs())
+    }
+
+    /// `a * b` as a Q32.32 number, for raw values `a` and `b`.
+    fn mul_bits(a: i64, b: i64) -> Fixed {
+        Fixed::saturate((a as i128 * b as i128) >> Self::FRAC_BITS)
+    }
+
+    /// `a / b` as a Q32.32 number, for values `a` and `b` of the same scale.
+    fn div_bits(a: i64, b: i64) -> Fixed {
+        let (num, den) = ((a as i128) << Self::FRAC_BITS, b as i128);
+        let quotient = num / den;
+        let floor = if num % den != 0 && (num < 0) != (den < 0) {
+            quotient - 1
+        } else {
+            quotient
+        };
+        Fixed::saturate(floor)
+    }
+
+    fn saturate(value: i128) -> Fixed {
+        Fixed(value.clamp(i64::MIN as i128, i64::MAX as i128) as i64)
+    }
+}
+
+impl std::ops::Add for Fixed {
+    type Output = Fixed;
+    fn add(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl std::ops::Sub for Fixed {
+    type Output = Fixed;
+    fn sub(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl std::ops::Neg for Fixed {
+    type Output = Fixed;
+    fn neg(self) -> Fixed {
+        Fixed(self.0.saturating_neg())
+    }
+}
+
+impl std::ops::Mul for Fixed {
+    type Output = Fixed;
+    fn mul(self, rhs: Fixed) -> Fixed {
+        Fixed::mul_bits(self.0, rhs.0)
+    }
+}
+
+impl std::ops::Div for Fixed {
+    type Output = Fixed;
+    /// Panics if `rhs` is zero.
+    fn div(self, rhs: Fixed) -> Fixed {
+        Fixed::div_bits(self.0, rhs.0)
+    }
+}
+
+impl std::fmt::Display for Fixed {
+    /// Decimal form with 6 fractional digits (truncated), e.g. `-1.250000`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        let int = abs >> Self::FRAC_BITS;
+        let frac = ((abs & (Self::ONE.0 as u64 - 1)) * 1_000_000) >> Self::FRAC_BITS;
+        write!(f, "{}{}.{:06}", sign, int, frac)
+    }
+}
+
+/// 3D vector of fixed-point numbers (sub-unit positions, velocities...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub struct FixedVec3 {
+    pub x: Fixed,
+    pub y: Fixed,
+    pub z: Fixed,
+}
+
+impl FixedVec3 {
+    pub const ZERO: FixedVec3 = FixedVec3 {
+        x: Fixed::ZERO,
+        y: Fixed::ZERO,
+        z: Fixed::ZERO,
+    };
+
+    #[inline]
+    pub fn new(x: Fixed, y: Fixed, z: Fixed) -> Self {
+        FixedVec3 { x, y, z }
+    }
+
+    /// Position of the grid cell `pos` (exact).
+    #[inline]
+    pub fn from_position(pos: Position) -> Self {
+        FixedVec3::new(
+            Fixed::from_int(pos.x),
+            Fixed::from_int(pos.y),
+            Fixed::from_int(pos.z),
+        )
+    }
+
+    /// Grid cell containing this point (each axis floored).
+    #[inline]
+    pub fn to_position(self) -> Position {
+        Position::new(self.x.floor(), self.y.floor(), self.z.floor())
+    }
+
+    /// Multiply every axis by `factor`.
+    pub fn scale(self, factor: Fixed) -> Self {
+        FixedVec3::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+
+    pub fn dot(self, other: FixedVec3) -> Fixed {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Squared length (no square root: stays exact).
+    pub fn length_squared(self) -> Fixed {
+        self.dot(self)
+    }
+}
+
+impl std::ops::Add for FixedVec3 {
+    type Output = FixedVec3;
+    fn add(self, rhs: FixedVec3) -> FixedVec3 {
+        FixedVec3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl std::ops::Sub for FixedVec3 {
+    type Output = FixedVec3;
+    fn sub(self, rhs: FixedVec3) -> FixedVec3 {
+        FixedVec3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl std::fmt::Display for FixedVec3 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/// Probability as an integer ratio `num / den` (0 <= num <= den, den > 0).
+/// Drawn with `IRng::chance` using integer arithmetic only.
+///
+/// The ratio is kept as given: equality is structural, so `1/2 != 2/4`
+/// even though both draw the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "ProbabilityRepr")]
+pub struct Probability {
+    num: u32,
+    den: u32,
+}
+
+/// Unchecked wire form of `Probability` (deserialization goes through
+/// `Probability::try_new`).
+#[derive(Deserialize)]
+struct ProbabilityRepr {
+    num: u32,
+    den: u32,
+}
+
+impl TryFrom<ProbabilityRepr> for Probability {
+    type Error = String;
+
+    fn try_from(repr: ProbabilityRepr) -> Result<Self, Self::Error> {
+        Probability::try_new(repr.num, repr.den)
+    }
+}
+
+impl Probability {
+    pub const NEVER: Probability = Probability { num: 0, den: 1 };
+    pub const ALWAYS: Probability = Probability { num: 1, den: 1 };
+
+    /// `num / den`. Panics (at compile time in consts) if `den` is 0 or
+    /// `num > den`.
+    pub const fn new(num: u32, den: u32) -> Self {
+        assert!(den > 0 && num <= den, "probability must be in [0, 1]");
+        Probability { num, den }
+    }
+
+    /// `num / den`, or an error if `den` is 0 or `num > den`.
+    pub fn try_new(num: u32, den: u32) -> Result<Self, String> {
+        if den == 0 || num > den {
+            return Err(format!("probability {}/{} is not in [0, 1]", num, den));
+        }
+        Ok(Probability { num, den })
+    }
+
+    /// `n` percent.
+    pub const fn percent(n: u32) -> Self {
+        Probability::new(n, 100)
+    }
+
+    /// `n` per thousand.
+    pub const fn per_mille(n: u32) -> Self {
+        Probability::new(n, 1000)
+    }
+
+    #[inline]
+    pub const fn num(&self) -> u32 {
+        self.num
+    }
+
+    #[inline]
+    pub const fn den(&self) -> u32 {
+        self.den
+    }
+}
+
+impl std::fmt::Display for Probability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.num, self.den)
+    }
+}
+
 /// World coordinates: zone + local position
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct WorldPos {
@@ -240,6 +584,20 @@ impl std::fmt::Display for WorldPos {
     }
 }
 
+/// One-way link from a local position of a zone to a position in another zone.
+/// An entity stepping onto `from` is transferred to `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Portal {
+    pub from: Position,
+    pub to: WorldPos,
+}
+
+impl std::fmt::Display for Portal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> {}", self.from, self.to)
+    }
+}
+
 // ============================================================================
 // Entity state
 // ============================================================================
@@ -281,6 +639,24 @@ pub enum EntityState {
     Dead,
 }
 
+/// What happens to an attached entity when its parent is despawned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum OnParentDespawn {
+    /// Detach: the entity stays in the world where its parent was
+    #[default]
+    Orphan,
+    /// Despawn the entity (and its own children, per their policy)
+    Cascade,
+}
+
+/// Link from an entity to the entity holding it (inventory slot, socket...).
+/// An attached entity shares its parent's position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Attachment {
+    pub parent: EntityId,
+    pub on_parent_despawn: OnParentDespawn,
+}
+
 // ============================================================================
 // World metadata
 // ============================================================================
@@ -295,6 +671,10 @@ pub enum EntityState {
 /// `last_event_id` is the cursor into the WAL. On recovery:
 /// 1. Load snapshot (which contains state at `last_saved_tick`)
 /// 2. Replay all events with `event_id > last_event_id`
+///
+/// ## Forks
+/// A forked world starts as a copy of its parent at `forked_from`: its WAL
+/// holds the parent's events up to that point, then its own.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorldMeta {
     /// Unique world identifier (derived from seed)
@@ -315,12 +695,26 @@ pub struct WorldMeta {
     pub last_event_id: EventId,
     /// Version of the format (for migrations)
     pub format_version: u32,
+    /// Where this world was forked from (None for an original world)
+    #[serde(default)]
+    pub forked_from: Option<ForkPoint>,
 }
 
 impl WorldMeta {
     pub const CURRENT_FORMAT_VERSION: u32 = 2; // Bumped for crash recovery support
 }
 
+/// Point of a parent world's history a fork was taken at.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkPoint {
+    /// World the fork was copied from
+    pub parent_world_id: String,
+    /// Last event of the parent kept by the fork
+    pub event_id: EventId,
+    /// Tick of the parent at that event
+    pub tick: Tick,
+}
+
 // ============================================================================
 // Result types
 // ============================================================================
@@ -378,5 +772,56 @@ mod tests {
         let a = Position::new(0, 0, 0);
         let b = Position::new(3, 4, 0);
         assert_eq!(a.manhattan_distance(&b), 7);
+        assert_eq!(a.distance_squared(&b), 25);
+
+        let min = Position::new(i32::MIN, i32::MIN, i32::MIN);
+        let max = Position::new(i32::MAX, i32::MAX, i32::MAX);
+        let span = u32::MAX as u128;
+        assert_eq!(min.manhattan_distance(&max), 3 * u32::MAX as u64);
+        assert_eq!(min.distance_squared(&max), 3 * span * span);
+        assert_eq!(max.distance_squared(&min), 3 * span * span);
+    }
+
+    #[test]
+    fn fixed_arithmetic_is_exact() {
+        let a = Fixed::from_ratio(3, 2);
+        let b = Fixed::from_int(-2);
+
+        assert_eq!(a + b, Fixed::from_ratio(-1, 2));
+        assert_eq!(a * b, Fixed::from_int(-3));
+        assert_eq!(b / a, Fixed::from_ratio(-4, 3));
+        assert_eq!(Fixed::from_ratio(1, -3), Fixed::from_ratio(-1, 3));
+        assert_eq!(Fixed::from_ratio(-1, 2).floor(), -1);
+        assert_eq!(Fixed::from_ratio(-1, 2).round(), 0);
+        assert_eq!(a.fract(), Fixed::HALF);
+        assert_eq!(Fixed::MAX + Fixed::ONE, Fixed::MAX);
+        assert_eq!(Fixed::from_ratio(-5, 4).to_string(), "-1.250000");
+    }
+
+    #[test]
+    fn probability_deserialization_is_checked() {
+        let parse = |json| serde_json::from_str::<Probability>(json);
+
+        assert_eq!(
+            parse(r#"{"num":1,"den":4}"#).unwrap(),
+            Probability::new(1, 4)
+        );
+        assert!(parse(r#"{"num":1,"den":0}"#).is_err());
+        assert!(parse(r#"{"num":5,"den":4}"#).is_err());
+        assert_ne!(Probability::new(1, 2), Probability::new(2, 4));
+    }
+
+    #[test]
+    fn fixed_vectors_map_to_grid_cells() {
+        let half = Fixed::HALF;
+        let p =
+            FixedVec3::from_position(Position::new(1, -1, 0)) - FixedVec3::new(half, half, half);
+
+        assert_eq!(p.to_position(), Position::new(0, -2, -1));
+        assert_eq!(p.scale(Fixed::from_int(2)).x, Fixed::ONE);
+        assert_eq!(
+            FixedVec3::new(Fixed::from_int(3), Fixed::from_int(4), Fixed::ZERO).length_squared(),
+            Fixed::from_int(25)
+        );
     }
 }